@@ -0,0 +1,228 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process;
+
+use hawk_processing::html::render_report;
+use hawk_processing::minecraft::strip_color_codes;
+use hawk_processing::report::diff::ReportDiff;
+use hawk_processing::report::raw;
+use hawk_processing::report::report::Report;
+use hawk_processing::report::validation::validate_report;
+
+const USAGE: &str = "Usage:
+    hawk validate [FILE]
+    hawk process [--lenient] [--pretty] [FILE]
+    hawk render [--lenient] [FILE]
+    hawk merge [--pretty] FILE...
+    hawk diff [--json] BEFORE AFTER
+    hawk strip-colors [TEXT]...
+
+Reads the raw report from FILE, or from the standard input if FILE is missing or is `-`.
+
+Commands:
+    validate        Checks a raw report and lists every problem found in it.
+    process         Processes a raw report and writes the processed JSON report.
+    render          Processes a raw report and writes it as a standalone HTML page.
+    merge           Merges the raw reports of the same match from each FILE, and writes
+                    the merged raw report.
+    diff            Compares two processed reports of the same game, and lists the players
+                    added, removed and changed (statistics, damages, kills, deaths, rank).
+    strip-colors    Strips Minecraft color codes from TEXT, or from each line of the
+                    standard input if no TEXT is given.
+
+Options:
+    --lenient       Repairs broken player references instead of failing.
+    --pretty        Pretty-prints the written JSON report.
+    --json          Writes the differences as JSON instead of text.";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.split_first() {
+        Some((command, args)) => match command.as_str() {
+            "validate" => validate(args),
+            "process" => process(args),
+            "render" => render(args),
+            "merge" => merge(args),
+            "diff" => diff(args),
+            "strip-colors" => strip_colors(args),
+            "-h" | "--help" | "help" => {
+                println!("{}", USAGE);
+                Ok(())
+            }
+            command => Err(format!("Unknown command: {}\n\n{}", command, USAGE)),
+        },
+        None => Err(USAGE.to_string()),
+    };
+
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
+
+fn validate(args: &[String]) -> Result<(), String> {
+    let (_, input) = parse_args(args, &[])?;
+    let problems = validate_report(&read_raw_report(input)?);
+
+    if problems.is_empty() {
+        return output("The report is valid.");
+    }
+
+    for problem in &problems {
+        output(&format!("{}: {} ({})", problem.path, problem.message, problem.code))?;
+    }
+
+    Err(format!("The report is invalid ({} problems found).", problems.len()))
+}
+
+fn process(args: &[String]) -> Result<(), String> {
+    let (flags, input) = parse_args(args, &["--lenient", "--pretty"])?;
+    let report = process_raw_report(input, flags.contains(&"--lenient"))?;
+
+    let json_report = match flags.contains(&"--pretty") {
+        true => serde_json::to_string_pretty(&report),
+        false => serde_json::to_string(&report),
+    }
+    .map_err(|error| format!("Unable to convert report to JSON string: {}", error))?;
+
+    output(&json_report)
+}
+
+fn render(args: &[String]) -> Result<(), String> {
+    let (flags, input) = parse_args(args, &["--lenient"])?;
+    let report = process_raw_report(input, flags.contains(&"--lenient"))?;
+
+    output(&render_report(&report))
+}
+
+fn merge(args: &[String]) -> Result<(), String> {
+    let (flags, inputs) = parse_args_with_inputs(args, &["--pretty"])?;
+
+    let raw_reports = inputs
+        .into_iter()
+        .map(|input| read_raw_report(Some(input)))
+        .collect::<Result<Vec<raw::Report>, String>>()?;
+
+    let merged = raw::merge::merge_reports(raw_reports)
+        .map_err(|error| format!("Unable to merge reports: {}", error))?;
+
+    let json_report = match flags.contains(&"--pretty") {
+        true => serde_json::to_string_pretty(&merged),
+        false => serde_json::to_string(&merged),
+    }
+    .map_err(|error| format!("Unable to convert report to JSON string: {}", error))?;
+
+    output(&json_report)
+}
+
+fn diff(args: &[String]) -> Result<(), String> {
+    let (flags, inputs) = parse_args_with_inputs(args, &["--json"])?;
+
+    let (before, after) = match inputs.as_slice() {
+        [before, after] => (read_processed_report(before)?, read_processed_report(after)?),
+        _ => return Err(format!("Two processed reports are expected.\n\n{}", USAGE)),
+    };
+
+    let diff = ReportDiff::between(&before, &after)
+        .map_err(|error| format!("Unable to compare reports: {}", error))?;
+
+    match flags.contains(&"--json") {
+        true => output(
+            &serde_json::to_string_pretty(&diff)
+                .map_err(|error| format!("Unable to convert diff to JSON string: {}", error))?,
+        ),
+        false => output(diff.to_string().trim_end()),
+    }
+}
+
+fn strip_colors(args: &[String]) -> Result<(), String> {
+    if !args.is_empty() {
+        return output(&strip_color_codes(args.join(" ")));
+    }
+
+    for line in read_input(None)?.lines() {
+        output(&strip_color_codes(line.to_string()))?;
+    }
+
+    Ok(())
+}
+
+/// Splits the arguments into the given known flags and an optional input file.
+fn parse_args<'a>(
+    args: &'a [String],
+    known_flags: &[&'static str],
+) -> Result<(Vec<&'static str>, Option<&'a str>), String> {
+    let (flags, inputs) = parse_args_with_inputs(args, known_flags)?;
+
+    match inputs.as_slice() {
+        [] => Ok((flags, None)),
+        [input] => Ok((flags, Some(*input))),
+        [_, unexpected, ..] => Err(format!("Unexpected argument: {}", unexpected)),
+    }
+}
+
+/// Splits the arguments into the given known flags and any number of input files.
+fn parse_args_with_inputs<'a>(
+    args: &'a [String],
+    known_flags: &[&'static str],
+) -> Result<(Vec<&'static str>, Vec<&'a str>), String> {
+    let mut flags = Vec::new();
+    let mut inputs = Vec::new();
+
+    for arg in args {
+        match known_flags.iter().find(|flag| *flag == arg) {
+            Some(flag) => flags.push(*flag),
+            None if arg.starts_with("--") => return Err(format!("Unknown option: {}", arg)),
+            None => inputs.push(arg.as_str()),
+        }
+    }
+
+    Ok((flags, inputs))
+}
+
+fn output(line: &str) -> Result<(), String> {
+    writeln!(io::stdout(), "{}", line)
+        .map_err(|error| format!("Unable to write to the standard output: {}", error))
+}
+
+fn read_input(input: Option<&str>) -> Result<String, String> {
+    match input {
+        Some(path) if path != "-" => fs::read_to_string(path)
+            .map_err(|error| format!("Unable to read {}: {}", path, error)),
+        _ => {
+            let mut buffer = String::new();
+            io::stdin()
+                .read_to_string(&mut buffer)
+                .map_err(|error| format!("Unable to read the standard input: {}", error))?;
+            Ok(buffer)
+        }
+    }
+}
+
+/// Reads a raw JSON report, upgrading it to the current report format if it was generated using an
+/// older one.
+fn read_raw_report(input: Option<&str>) -> Result<raw::Report, String> {
+    let raw_report = serde_json::from_str(read_input(input)?.as_str())
+        .map_err(|error| format!("Invalid JSON: {}", error))?;
+    let raw_report = raw::migrations::migrate(raw_report)
+        .map_err(|error| format!("Invalid report: {}", error))?;
+
+    serde_json::from_value(raw_report).map_err(|error| format!("Invalid JSON: {}", error))
+}
+
+fn read_processed_report(input: &str) -> Result<Report, String> {
+    serde_json::from_str(read_input(Some(input))?.as_str())
+        .map_err(|error| format!("Invalid processed report: {}", error))
+}
+
+fn process_raw_report(input: Option<&str>, lenient: bool) -> Result<Report, String> {
+    let raw_report = read_raw_report(input)?;
+
+    match lenient {
+        true => Report::from_raw_lenient(raw_report),
+        false => Report::from_raw(raw_report),
+    }
+    .map_err(|error| format!("Invalid report: {}", error))
+}
@@ -0,0 +1,494 @@
+//! Renders a processed report into a single, self-contained HTML page, so reports can be shared
+//! without the web interface.
+
+use std::time::Duration;
+
+use crate::minecraft::{parse_color_codes, strip_color_codes};
+use crate::report::aggregates::PlayerAlterationsAggregate;
+use crate::report::damage::DamageCause;
+use crate::report::event::{EventIcon, EventType};
+use crate::report::item::names::Locale;
+use crate::report::player::{
+    DisplayedPlayerStatistics, DisplayedStatistics, SimplePlayer, Statistic,
+};
+use crate::report::report::Report;
+use crate::report::settings::GameMode;
+use crate::report::team::TeamColor;
+
+const STYLESHEET: &str = "
+body { font-family: sans-serif; background: #1d1d1d; color: #e0e0e0; margin: 0 auto; max-width: 60rem; padding: 1rem; }
+h1 { font-size: 2.2rem; margin-bottom: .2rem; }
+h2 { border-bottom: 2px solid #444; padding-bottom: .3rem; margin-top: 2.5rem; }
+header p, .muted { color: #999; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: .3rem .6rem; border-bottom: 1px solid #333; }
+td.number, th.number { text-align: right; }
+ul.timeline { list-style: none; padding: 0; }
+ul.timeline li { border-left: 4px solid #5555FF; margin-bottom: .6rem; padding-left: .8rem; }
+ul.timeline li.gold { border-color: #FFAA00; }
+ul.timeline li.green { border-color: #55FF55; }
+ul.timeline li.red { border-color: #FF5555; }
+ul.timeline time { color: #999; font-size: .9em; margin-left: .5rem; }
+.winners { font-size: 1.3rem; }
+";
+
+/// Renders the processed report as a standalone HTML document.
+pub fn render_report(report: &Report) -> String {
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\" />\n");
+    html.push_str(&format!(
+        "<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        escape_html(&strip_color_codes(report.title.clone())),
+        STYLESHEET
+    ));
+
+    render_header(&mut html, report);
+
+    if report.settings.summary.enabled {
+        render_summary(&mut html, report);
+    }
+
+    if report.settings.damages.enabled {
+        render_damages(&mut html, report);
+    }
+
+    if report.settings.players.enabled {
+        render_statistics(&mut html, report);
+    }
+
+    html.push_str("</body>\n</html>\n");
+
+    html
+}
+
+fn render_header(html: &mut String, report: &Report) {
+    html.push_str(&format!(
+        "<header>\n<h1>{}</h1>\n",
+        minecraft(&report.title)
+    ));
+
+    if report.settings.date {
+        html.push_str(&format!(
+            "<p><time datetime=\"{}\">{}</time></p>\n",
+            report.date.to_rfc3339(),
+            report.date.format("%Y-%m-%d %H:%M")
+        ));
+    }
+
+    let mut details = Vec::new();
+
+    if report.settings.players_count {
+        details.push(match report.players.len() {
+            1 => String::from("1 player"),
+            count => format!("{} players", count),
+        });
+    }
+    if let Some(minecraft) = &report.minecraft {
+        details.push(format!("Minecraft {}", escape_html(minecraft)));
+    }
+    if let Some(generator) = &report.settings.generator {
+        details.push(match &generator.link {
+            Some(link) => format!(
+                "Generated by <a href=\"{}\">{}</a>",
+                escape_html(link),
+                escape_html(&generator.name)
+            ),
+            None => format!("Generated by {}", escape_html(&generator.name)),
+        });
+    }
+
+    if !details.is_empty() {
+        html.push_str(&format!("<p>{}</p>\n", details.join(" · ")));
+    }
+
+    html.push_str("</header>\n");
+}
+
+fn render_summary(html: &mut String, report: &Report) {
+    html.push_str("<section id=\"summary\">\n<h2>Summary</h2>\n");
+
+    if report.settings.winners && !report.winners.is_empty() {
+        html.push_str(&format!(
+            "<p class=\"winners\">Winners: {}</p>\n",
+            report
+                .winners
+                .iter()
+                .map(player)
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+    }
+
+    if report.settings.summary.history && !report.events.is_empty() {
+        html.push_str("<h3>Game timeline</h3>\n<ul class=\"timeline\">\n");
+
+        for event in &report.events {
+            html.push_str(&format!(
+                "<li class=\"{}\"><strong>{}</strong><time datetime=\"{}\">{}</time>",
+                match event.event_type {
+                    EventType::Blue => "blue",
+                    EventType::Gold => "gold",
+                    EventType::Green => "green",
+                    EventType::Red => "red",
+                },
+                minecraft(&event.title),
+                event.date.to_rfc3339(),
+                duration(&event.since_beginning)
+            ));
+
+            if let Some(description) = &event.description {
+                html.push_str(&format!("<br />{}", minecraft(description)));
+            }
+
+            if let EventIcon::Player { uuid } = &event.icon {
+                if let Some(icon_player) = report.players.iter().find(|p| &p.uuid == uuid) {
+                    html.push_str(&format!(
+                        " <span class=\"muted\">({})</span>",
+                        escape_html(&icon_player.name)
+                    ));
+                }
+            }
+
+            html.push_str("</li>\n");
+        }
+
+        html.push_str("</ul>\n");
+    }
+
+    if report.settings.summary.players {
+        if report.settings.summary.teams && !report.teams.is_empty() {
+            html.push_str("<h3>Teams</h3>\n<ul>\n");
+
+            for team in &report.teams {
+                html.push_str(&format!(
+                    "<li><span style=\"color: #{};\">{}</span>: {}</li>\n",
+                    team_color(team.color),
+                    escape_html(&team.name),
+                    team.players
+                        .iter()
+                        .map(player)
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ));
+            }
+
+            html.push_str("</ul>\n");
+        } else {
+            html.push_str(&format!(
+                "<h3>Players</h3>\n<p>{}</p>\n",
+                report
+                    .players
+                    .iter()
+                    .map(|p| escape_html(&p.name))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ));
+        }
+    }
+
+    html.push_str("</section>\n");
+}
+
+fn render_damages(html: &mut String, report: &Report) {
+    html.push_str("<section id=\"damages\">\n<h2>Damages</h2>\n");
+
+    if report.settings.damages.damages_per_players {
+        let mut aggregates: Vec<(&String, &PlayerAlterationsAggregate)> = report
+            .players
+            .iter()
+            .filter_map(|p| {
+                report
+                    .aggregates
+                    .players_damages
+                    .get(&p.uuid)
+                    .map(|aggregate| (&p.name, aggregate))
+            })
+            .collect();
+
+        aggregates.sort_by_key(|(_, aggregate)| aggregate.rank);
+
+        html.push_str("<table>\n<thead><tr>");
+        html.push_str(
+            "<th class=\"number\">Rank</th><th>Player</th><th class=\"number\">Kills</th>",
+        );
+        if report.settings.game_mode != GameMode::Elimination {
+            html.push_str("<th class=\"number\">Deaths</th>");
+        }
+        if report.settings.damages.display_killer {
+            html.push_str("<th>Killed by</th>");
+        }
+        html.push_str("<th class=\"number\">Damages caused</th>");
+        html.push_str("<th class=\"number\">Damages taken</th>");
+        html.push_str("<th class=\"number\">Heals</th>");
+        if report.settings.players.play_time {
+            html.push_str("<th class=\"number\">Play time</th>");
+        }
+        html.push_str("</tr></thead>\n<tbody>\n");
+
+        for (name, aggregate) in aggregates {
+            html.push_str(&format!(
+                "<tr><td class=\"number\">{}</td><td>{}</td><td class=\"number\">{}</td>",
+                aggregate.rank,
+                escape_html(name),
+                aggregate.kills.len()
+            ));
+            if report.settings.game_mode != GameMode::Elimination {
+                html.push_str(&format!("<td class=\"number\">{}</td>", aggregate.deaths()));
+            }
+            if report.settings.damages.display_killer {
+                html.push_str(&format!(
+                    "<td>{}",
+                    aggregate
+                        .killed_by
+                        .as_ref()
+                        .map(damage_cause)
+                        .unwrap_or_default()
+                ));
+                if let Some(death_cause) = aggregate
+                    .death_cause
+                    .as_ref()
+                    .filter(|cause| Some(*cause) != aggregate.killed_by.as_ref())
+                {
+                    html.push_str(&format!(
+                        " <span class=\"muted\">({})</span>",
+                        damage_cause(death_cause)
+                    ));
+                }
+                if !aggregate.assisted_by.is_empty() {
+                    html.push_str(&format!(
+                        ", assisted by {}",
+                        aggregate
+                            .assisted_by
+                            .iter()
+                            .map(|assist| format!("{} ({:.0}%)", player(&assist.player), assist.share))
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ));
+                }
+                html.push_str("</td>");
+            }
+            html.push_str(&format!(
+                "<td class=\"number\">{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td>",
+                hearts(aggregate.damages_caused_total),
+                hearts(aggregate.damages_taken_total),
+                hearts(aggregate.heals_total)
+            ));
+            if report.settings.players.play_time {
+                html.push_str(&format!(
+                    "<td class=\"number\">{}</td>",
+                    duration(&aggregate.game_duration)
+                ));
+            }
+            html.push_str("</tr>\n");
+        }
+
+        html.push_str("</tbody>\n</table>\n");
+    }
+
+    let environmental = &report.aggregates.environmental_damages;
+
+    if report.settings.damages.damages_from_environment
+        && !(environmental.entities.is_empty() && environmental.environment.is_empty())
+    {
+        html.push_str("<h3>Damages from the environment</h3>\n<table>\n<tbody>\n");
+
+        for (cause, damages) in environmental
+            .entities
+            .iter()
+            .chain(environmental.environment.iter())
+        {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"number\">{}</td></tr>\n",
+                escape_html(&humanize(cause)),
+                hearts(*damages)
+            ));
+        }
+
+        html.push_str("</tbody>\n</table>\n");
+    }
+
+    html.push_str("</section>\n");
+}
+
+fn render_statistics(html: &mut String, report: &Report) {
+    html.push_str("<section id=\"statistics\">\n<h2>Statistics</h2>\n");
+
+    if report.settings.players.global_statistics {
+        render_player_statistics(
+            html,
+            "Global statistics",
+            &report.aggregates.global_statistics,
+        );
+    }
+
+    for player in &report.players {
+        if let Some(statistics) = &player.displayed_statistics {
+            render_player_statistics(html, &escape_html(&player.name), statistics);
+        }
+    }
+
+    html.push_str("</section>\n");
+}
+
+fn render_player_statistics(
+    html: &mut String,
+    title: &str,
+    statistics: &DisplayedPlayerStatistics,
+) {
+    let groups = [
+        ("General statistics", &statistics.generic),
+        ("Mined blocks", &statistics.mined),
+        ("Picked-up items", &statistics.picked_up),
+        ("Used items", &statistics.used),
+    ];
+
+    let groups: Vec<(&str, &DisplayedStatistics)> = groups
+        .iter()
+        .filter_map(|(group_title, group)| match group {
+            Some(group) if !(group.visible.is_empty() && group.hidden.is_empty()) => {
+                Some((*group_title, group))
+            }
+            _ => None,
+        })
+        .collect();
+
+    if groups.is_empty() {
+        return;
+    }
+
+    html.push_str(&format!("<h3>{}</h3>\n", title));
+
+    for (group_title, group) in groups {
+        render_statistics_group(html, group_title, group);
+    }
+}
+
+fn render_statistics_group(html: &mut String, title: &str, statistics: &DisplayedStatistics) {
+    html.push_str(&format!("<h4>{}</h4>\n<table>\n<tbody>\n", title));
+
+    for statistic in statistics.visible.iter().chain(statistics.hidden.iter()) {
+        let (id, value) = match statistic {
+            Statistic::Duration {
+                id, duration: d, ..
+            } => (id, duration(d)),
+            Statistic::Distance {
+                id, distance, unit, ..
+            } => (id, format!("{:.1} {}", distance, unit)),
+            Statistic::Hearts { id, hearts, .. } => (id, format!("{:.1} ❤", hearts)),
+            Statistic::Times { id, raw } => (id, format!("{} times", raw)),
+            Statistic::Number { id, raw } => (id, raw.to_string()),
+        };
+
+        html.push_str(&format!(
+            "<tr><td>{}</td><td class=\"number\">{}</td></tr>\n",
+            escape_html(&humanize(id)),
+            value
+        ));
+    }
+
+    html.push_str("</tbody>\n</table>\n");
+}
+
+/// Escapes a string so it can be safely inserted into HTML.
+pub fn escape_html(raw: &str) -> String {
+    let mut escaped = String::with_capacity(raw.len());
+
+    raw.chars().for_each(|c| match c {
+        '&' => escaped.push_str("&amp;"),
+        '<' => escaped.push_str("&lt;"),
+        '>' => escaped.push_str("&gt;"),
+        '"' => escaped.push_str("&quot;"),
+        '\'' => escaped.push_str("&#39;"),
+        _ => escaped.push(c),
+    });
+
+    escaped
+}
+
+/// Escapes then converts Minecraft formatting codes to HTML.
+fn minecraft(raw: &str) -> String {
+    parse_color_codes(escape_html(raw))
+}
+
+fn player(player: &SimplePlayer) -> String {
+    format!(
+        "<span style=\"color: #{};\">{}</span>",
+        team_color(player.color),
+        escape_html(&player.name)
+    )
+}
+
+/// Describes a damage cause, with the weapon of the player or entity (including its potion
+/// effects, e.g. for a witch throwing a splash potion). Magic and poison damages carry no item, so
+/// the potion behind them is unknown: they are only named.
+fn damage_cause(cause: &DamageCause) -> String {
+    match cause {
+        DamageCause::Player(cause) => match &cause.weapon {
+            Some(weapon) => format!(
+                "{} <span class=\"muted\">with {}</span>",
+                player(&cause.player),
+                minecraft(&weapon.display_name(Locale::En))
+            ),
+            None => player(&cause.player),
+        },
+        DamageCause::Entity(cause) => match &cause.weapon {
+            Some(weapon) => format!(
+                "{} <span class=\"muted\">with {}</span>",
+                escape_html(&humanize(&cause.entity)),
+                minecraft(&weapon.display_name(Locale::En))
+            ),
+            None => escape_html(&humanize(&cause.entity)),
+        },
+        cause => humanize(&cause.to_string()),
+    }
+}
+
+/// Converts a Minecraft identifier (e.g. `minecraft:cave_spider` or `minecraft.walk_one_cm`) into
+/// a somewhat readable string.
+fn humanize(id: &str) -> String {
+    let id = id.replace("minecraft.custom:", "");
+    let id = id.rsplit([':', '.']).next().unwrap_or(&id);
+    let id = id.replace('_', " ");
+
+    let mut chars = id.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Formats health points into hearts (one heart being two health points).
+fn hearts(points: u32) -> String {
+    format!("{} ❤", points as f32 / 2_f32)
+}
+
+fn duration(duration: &Duration) -> String {
+    let seconds = duration.as_secs();
+
+    match seconds / 3600 {
+        0 => format!("{:02}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{}:{:02}:{:02}", hours, (seconds % 3600) / 60, seconds % 60),
+    }
+}
+
+fn team_color(color: TeamColor) -> &'static str {
+    match color {
+        TeamColor::Black => "000000",
+        TeamColor::DarkBlue => "0000AA",
+        TeamColor::DarkGreen => "00AA00",
+        TeamColor::DarkAqua => "00AAAA",
+        TeamColor::DarkRed => "AA0000",
+        TeamColor::DarkPurple => "AA00AA",
+        TeamColor::Gold => "FFAA00",
+        TeamColor::Gray => "AAAAAA",
+        TeamColor::DarkGray => "555555",
+        TeamColor::Blue => "5555FF",
+        TeamColor::Green => "55FF55",
+        TeamColor::Aqua => "55FFFF",
+        TeamColor::Red => "FF5555",
+        TeamColor::LightPurple => "FF55FF",
+        TeamColor::Yellow => "FFFF55",
+        TeamColor::White | TeamColor::None => "FFFFFF",
+    }
+}
@@ -5,6 +5,8 @@ extern crate failure;
 extern crate lazy_static;
 extern crate regex;
 extern crate roman;
+#[macro_use]
+extern crate schemars;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
@@ -21,10 +23,35 @@ use pyo3::wrap_pyfunction;
 
 use minecraft::{parse_color_codes, strip_color_codes};
 
+pub mod html;
 pub mod minecraft;
 pub mod report;
 
-/// process_report(raw_json_report, /)
+/// Parses a raw JSON report, upgrading it to the current report format if it was generated using an
+/// older one.
+fn parse_raw_report(raw_json_report: &str) -> PyResult<report::raw::Report> {
+    let raw_report = serde_json::from_str(raw_json_report)
+        .map_err(|error| PyValueError::new_err(format!("Invalid JSON: {}", error)))?;
+    let raw_report = report::raw::migrations::migrate(raw_report)
+        .map_err(|error| PyValueError::new_err(format!("Invalid report: {}", error)))?;
+
+    serde_json::from_value(raw_report)
+        .map_err(|error| PyValueError::new_err(format!("Invalid JSON: {}", error)))
+}
+
+/// Parses processed JSON reports, as returned by process_report.
+fn parse_processed_reports(processed_json_reports: &[String]) -> PyResult<Vec<report::report::Report>> {
+    processed_json_reports
+        .iter()
+        .map(|processed_json_report| {
+            serde_json::from_str(processed_json_report.as_str()).map_err(|error| {
+                PyValueError::new_err(format!("Invalid processed report: {}", error))
+            })
+        })
+        .collect()
+}
+
+/// process_report(raw_json_report, /, lenient=False)
 /// --
 ///
 /// This method takes a raw JSON report as string, and returns a dict containing the following keys:
@@ -35,14 +62,23 @@ pub mod report;
 /// - "generator_name": the generator name extracted from the received report (may be absent);
 /// - "generator_link": the generator link extracted from the received report (may be absent).
 ///
+/// If `lenient` is true, broken player references are repaired (using placeholder players) instead
+/// of failing, and the problems found are listed in the "warnings" key of the processed report.
+///
+/// Reports using an older format are upgraded to the current one before being processed.
+///
 /// If the JSON is invalid or does not complies to the report format, a ValueError will be raised.
 /// If for some reason the processed report cannot be converted back to a JSON string, a
 /// RuntimeError will be raised, but this should never happens (except if there is a bug in the
 /// Rust implementation).
-#[pyfunction]
-fn process_report(raw_json_report: String) -> PyResult<HashMap<String, String>> {
-    match serde_json::from_str(raw_json_report.as_str()) {
-        Ok(raw_report) => match report::report::Report::from_raw(raw_report) {
+#[pyfunction(lenient = "false")]
+fn process_report(raw_json_report: String, lenient: bool) -> PyResult<HashMap<String, String>> {
+    match parse_raw_report(raw_json_report.as_str()) {
+        Ok(raw_report) => match if lenient {
+            report::report::Report::from_raw_lenient(raw_report)
+        } else {
+            report::report::Report::from_raw(raw_report)
+        } {
             Ok(report) => match serde_json::to_string(&report) {
                 Ok(json_report) => {
                     let mut report_return = HashMap::new();
@@ -84,10 +120,132 @@ fn process_report(raw_json_report: String) -> PyResult<HashMap<String, String>>
             },
             Err(error) => Err(PyValueError::new_err(format!("Invalid report: {}", error))),
         },
-        Err(error) => Err(PyValueError::new_err(format!("Invalid JSON: {}", error))),
+        Err(error) => Err(error),
+    }
+}
+
+/// validate_report(raw_json_report, /)
+/// --
+///
+/// This method takes a raw JSON report as string, and checks it completely instead of stopping at
+/// the first problem like process_report does. It returns a list of dicts, one per problem found,
+/// with the following keys:
+/// - "path": where the problem is in the report, as a JSON-pointer-like path (e.g.
+///   "/damages/3/damagee");
+/// - "code": a machine-readable code for the problem (e.g. "MissingPlayerReference");
+/// - "message": a human-readable description of the problem.
+///
+/// If the report is valid, the returned list is empty.
+///
+/// If the JSON is invalid or does not complies to the report format, a ValueError will be raised.
+#[pyfunction]
+fn validate_report(raw_json_report: String) -> PyResult<Vec<HashMap<String, String>>> {
+    match parse_raw_report(raw_json_report.as_str()) {
+        Ok(raw_report) => Ok(report::validation::validate_report(&raw_report)
+            .into_iter()
+            .map(|problem| {
+                let mut problem_return = HashMap::new();
+
+                problem_return.insert("path".to_string(), problem.path);
+                problem_return.insert("code".to_string(), problem.code);
+                problem_return.insert("message".to_string(), problem.message);
+
+                problem_return
+            })
+            .collect()),
+        Err(error) => Err(error),
     }
 }
 
+/// merge_reports(raw_json_reports, /)
+/// --
+///
+/// This method takes a list of raw JSON reports of the same match (e.g. when a server restarted
+/// during the game and the plugin generated a report for each part of it) as strings, and returns
+/// a single raw JSON report as string, merging all of them. It can then be processed using
+/// process_report.
+///
+/// If one of the JSON is invalid or does not complies to the report format, if the reports are
+/// not from the same match, or if there is no report at all, a ValueError will be raised.
+#[pyfunction]
+fn merge_reports(raw_json_reports: Vec<String>) -> PyResult<String> {
+    let raw_reports = raw_json_reports
+        .iter()
+        .map(|raw_json_report| parse_raw_report(raw_json_report.as_str()))
+        .collect::<PyResult<Vec<report::raw::Report>>>()?;
+
+    match report::raw::merge::merge_reports(raw_reports) {
+        Ok(raw_report) => serde_json::to_string(&raw_report).map_err(|error| {
+            PyRuntimeError::new_err(format!("Unable to convert report to JSON string: {}", error))
+        }),
+        Err(error) => Err(PyValueError::new_err(format!("Invalid reports: {}", error))),
+    }
+}
+
+/// season_leaderboard(processed_json_reports, /)
+/// --
+///
+/// This method takes a list of processed JSON reports (as returned in the "processed_report" key
+/// by process_report) as strings, e.g. all the games of a season, and returns the season
+/// leaderboard as a JSON string. It contains the number of games and, for each player (tracked by
+/// UUID, even if they changed their name), their games played, wins, kills, deaths, average rank,
+/// total damages and summed statistics, best players first.
+///
+/// If one of the JSON is invalid or is not a processed report, a ValueError will be raised.
+/// If for some reason the leaderboard cannot be converted to a JSON string, a RuntimeError will be
+/// raised, but this should never happens (except if there is a bug in the Rust implementation).
+#[pyfunction]
+fn season_leaderboard(processed_json_reports: Vec<String>) -> PyResult<String> {
+    let reports = parse_processed_reports(&processed_json_reports)?;
+
+    serde_json::to_string(&report::season::Season::from_reports(&reports)).map_err(|error| {
+        PyRuntimeError::new_err(format!("Unable to convert leaderboard to JSON string: {}", error))
+    })
+}
+
+/// player_ratings(processed_json_reports, /)
+/// --
+///
+/// This method takes a list of processed JSON reports (as returned in the "processed_report" key
+/// by process_report) as strings, and rates the skill of their players, game after game in
+/// chronological order. It returns a JSON string mapping each player UUID to their rating, with
+/// their name, estimated skill ("mu"), uncertainty ("sigma"), conservative "rating" (to sort
+/// players by) and number of rated games.
+///
+/// Ratings only depend on the reports, so they can always be recomputed from the stored ones.
+///
+/// If one of the JSON is invalid or is not a processed report, a ValueError will be raised.
+/// If for some reason the ratings cannot be converted to a JSON string, a RuntimeError will be
+/// raised, but this should never happens (except if there is a bug in the Rust implementation).
+#[pyfunction]
+fn player_ratings(processed_json_reports: Vec<String>) -> PyResult<String> {
+    let reports = parse_processed_reports(&processed_json_reports)?;
+
+    serde_json::to_string(&report::rating::Ratings::from_reports(&reports)).map_err(|error| {
+        PyRuntimeError::new_err(format!("Unable to convert ratings to JSON string: {}", error))
+    })
+}
+
+/// report_schema(processed=False)
+/// --
+///
+/// This method returns the JSON Schema of the raw reports (the ones sent by the plugins) as a
+/// string, or of the processed reports if `processed` is true.
+///
+/// If for some reason the schema cannot be converted to a JSON string, a RuntimeError will be
+/// raised, but this should never happens (except if there is a bug in the Rust implementation).
+#[pyfunction(processed = "false")]
+fn report_schema(processed: bool) -> PyResult<String> {
+    let schema = match processed {
+        true => report::schema::processed_report_schema(),
+        false => report::schema::raw_report_schema(),
+    };
+
+    serde_json::to_string(&schema).map_err(|error| {
+        PyRuntimeError::new_err(format!("Unable to convert schema to JSON string: {}", error))
+    })
+}
+
 /// parse_minecraft_color_codes(raw_string, /)
 /// --
 ///
@@ -116,6 +274,29 @@ fn strip_minecraft_color_codes(raw_string: String) -> PyResult<String> {
     Ok(strip_color_codes(raw_string))
 }
 
+/// item_name(raw_json_item, /, locale="en")
+/// --
+///
+/// This method takes a Minecraft item, as a JSON string in the report format (e.g.
+/// '{"id": "minecraft:diamond_sword"}'), and returns its name as displayed in the game, followed
+/// by its enchantments (e.g. "Diamond Sword (Sharpness III, Unbreaking II)"), in the given locale
+/// ("en", "fr" or "it").
+///
+/// If the item was renamed, its custom name is used. It may contain Minecraft formatting codes.
+///
+/// If the JSON is invalid or is not an item, or if the locale is not supported, a ValueError will
+/// be raised.
+#[pyfunction(locale = "\"en\"")]
+fn item_name(raw_json_item: String, locale: &str) -> PyResult<String> {
+    let item: report::item::Item = serde_json::from_str(raw_json_item.as_str())
+        .map_err(|error| PyValueError::new_err(format!("Invalid item: {}", error)))?;
+    let locale: report::item::names::Locale = locale
+        .parse()
+        .map_err(|_| PyValueError::new_err(format!("Unsupported locale: {}", locale)))?;
+
+    Ok(item.display_name(locale))
+}
+
 /// to_roman(number, /)
 /// --
 ///
@@ -133,9 +314,15 @@ fn to_roman(number: i32) -> PyResult<String> {
 #[pymodule]
 fn hawk_processing(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_wrapped(wrap_pyfunction!(process_report))?;
+    m.add_wrapped(wrap_pyfunction!(validate_report))?;
+    m.add_wrapped(wrap_pyfunction!(merge_reports))?;
+    m.add_wrapped(wrap_pyfunction!(season_leaderboard))?;
+    m.add_wrapped(wrap_pyfunction!(player_ratings))?;
+    m.add_wrapped(wrap_pyfunction!(report_schema))?;
     m.add_wrapped(wrap_pyfunction!(parse_minecraft_color_codes))?;
     m.add_wrapped(wrap_pyfunction!(strip_minecraft_color_codes))?;
     m.add_wrapped(wrap_pyfunction!(to_roman))?;
+    m.add_wrapped(wrap_pyfunction!(item_name))?;
 
     Ok(())
 }
@@ -7,7 +7,7 @@ use regex::Regex;
 /// # Example
 ///
 /// ```
-/// use lib::minecraft::parse_color_codes;
+/// use hawk_processing::minecraft::parse_color_codes;
 /// assert_eq!(parse_color_codes(String::from("§2Dark green")), String::from("<span style=\"color: #00AA00;\">Dark green</span>"));
 /// ```
 pub fn parse_color_codes(raw_string: String) -> String {
@@ -149,7 +149,7 @@ pub fn parse_color_codes(raw_string: String) -> String {
 /// # Example
 ///
 /// ```
-/// use lib::minecraft::strip_color_codes;
+/// use hawk_processing::minecraft::strip_color_codes;
 /// assert_eq!(strip_color_codes(String::from("§2Dark green")), String::from("Dark green"));
 /// ```
 pub fn strip_color_codes(raw_string: String) -> String {
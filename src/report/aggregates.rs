@@ -5,40 +5,111 @@ use std::time::Duration;
 use chrono::{DateTime, FixedOffset};
 use uuid::Uuid;
 
-use crate::report::damage::{Damage, DamageCause};
+use crate::report::damage::{Assist, Damage, DamageCause};
 use crate::report::heal::Heal;
+use crate::report::item::{EnchantmentTag, Item};
+use crate::report::highlights::{MultiKill, PlayerHighlights};
 use crate::report::player::{Player, PlayerStatistics, DisplayedPlayerStatistics, SimplePlayer};
 use crate::report::report::since;
-use crate::report::settings::SettingsPlayers;
+use crate::report::settings::{GameMode, Settings, SettingsDamages, SettingsPlayers};
+use crate::report::team::Team;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Aggregate {
     pub global_statistics: DisplayedPlayerStatistics,
     pub players_damages: BTreeMap<Uuid, PlayerAlterationsAggregate>,
+    #[serde(default)]
+    pub teams_damages: BTreeMap<String, TeamAlterationsAggregate>,
+
+    /// What each player did to each other player: `head_to_head[attacker][victim]`.
+    #[serde(default)]
+    pub head_to_head: BTreeMap<Uuid, BTreeMap<Uuid, HeadToHead>>,
+
+    /// What each team did to each team (including itself, for friendly fire):
+    /// `teams_head_to_head[attacker][victim]`. Empty if damages per team are disabled.
+    #[serde(default)]
+    pub teams_head_to_head: BTreeMap<String, BTreeMap<String, HeadToHead>>,
+
+    /// The weapons used during the game, by players or entities, sorted by decreasing kills then
+    /// damages: the first one is the most lethal weapon of the game.
+    #[serde(default)]
+    pub weapons: Vec<WeaponAggregate>,
+
     pub environmental_damages: EnvironmentalDamagesAggregate,
 }
 
 impl Aggregate {
+    /// Aggregates the damages (grouped, see `Damage::group`) and heals of the game. The ungrouped
+    /// `hits` are used to replay the health of the players, and to date eliminations (as grouped
+    /// damages keep the date of their first hit).
     pub fn from_raw(
         players: &HashMap<Uuid, Rc<Player>>,
+        teams: &[Team],
         damages: &Vec<Damage>,
+        hits: &[Damage],
         heals: &Vec<Heal>,
         begin: &DateTime<FixedOffset>,
-        settings: &SettingsPlayers,
+        settings: &Settings,
     ) -> Self {
+        let eliminations = eliminations(hits, settings.game_mode);
+        let placements = Self::placements(players, &eliminations);
+
         Aggregate {
             global_statistics: Self::aggregate_global_statistics(
                 &players
                     .iter()
                     .filter_map(|(_uuid, player)| (player.as_ref()).statistics.clone())
                     .collect(),
-                settings
+                &settings.players
+            ),
+            players_damages: Self::aggregate_alterations(
+                players,
+                &Alterations { damages, hits, heals },
+                &eliminations,
+                &placements,
+                begin,
+                &settings.damages,
             ),
-            players_damages: Self::aggregate_alterations(players, damages, heals, begin),
+            teams_damages: match settings.damages.damages_per_team {
+                true => Self::aggregate_teams_alterations(
+                    teams,
+                    damages,
+                    heals,
+                    &eliminations,
+                    &placements,
+                ),
+                false => BTreeMap::new(),
+            },
+            head_to_head: Self::aggregate_head_to_head(damages, |player| Some(player.uuid)),
+            teams_head_to_head: match settings.damages.damages_per_team {
+                true => Self::aggregate_head_to_head(damages, |player| player.team.clone()),
+                false => BTreeMap::new(),
+            },
+            weapons: {
+                let mut weapons = Self::aggregate_weapons(damages);
+                weapons.sort_by(|a, b| {
+                    b.kills.cmp(&a.kills)
+                        .then_with(|| b.damages.cmp(&a.damages))
+                        .then_with(|| a.id.cmp(&b.id))
+                });
+                weapons
+            },
             environmental_damages: Self::aggregate_environmental_damages(damages),
         }
     }
 
+    /// Adds the highlights of the players (see `highlights::players_highlights`) to their
+    /// aggregates.
+    pub fn with_highlights(mut self, highlights: &HashMap<Uuid, PlayerHighlights>) -> Self {
+        for (uuid, aggregate) in self.players_damages.iter_mut() {
+            if let Some(highlights) = highlights.get(uuid) {
+                aggregate.apply_highlights(highlights);
+            }
+        }
+
+        self
+    }
+
     fn aggregate_global_statistics(statistics: &Vec<PlayerStatistics>, settings: &SettingsPlayers) -> DisplayedPlayerStatistics {
         DisplayedPlayerStatistics::calculate_displayed_statistics(&PlayerStatistics {
             generic: Some(Self::aggregate_single_statistic_group(
@@ -95,41 +166,24 @@ impl Aggregate {
 
     fn aggregate_alterations(
         players: &HashMap<Uuid, Rc<Player>>,
-        damages: &Vec<Damage>,
-        heals: &Vec<Heal>,
+        alterations: &Alterations,
+        eliminations: &HashMap<Uuid, DateTime<FixedOffset>>,
+        placements: &BTreeMap<Placed, u32>,
         begin: &DateTime<FixedOffset>,
+        settings: &SettingsDamages,
     ) -> BTreeMap<Uuid, PlayerAlterationsAggregate> {
-        let ranks: BTreeMap<Uuid, u8> = {
-            let mut lethal_damages: Vec<&Damage> =
-                damages.iter().filter(|damage| damage.lethal).collect();
-            lethal_damages.sort_by(|a, b| a.date.cmp(&b.date).reverse());
-
-            let ranked_deads: Vec<Uuid> = lethal_damages
-                .iter()
-                .map(|damage| damage.damagee.uuid)
-                .collect();
-
-            players
-                .iter()
-                .map(|(uuid, _player)| uuid)
-                .filter(|uuid| !ranked_deads.contains(uuid))
-                .chain(ranked_deads.iter())
-                .enumerate()
-                .map(|(index, uuid)| (uuid.clone(), index as u8 + 1))
-                .collect()
-        };
-
         players
             .iter()
-            .map(|(uuid, _player)| {
+            .map(|(uuid, player)| {
                 (
                     uuid.clone(),
                     Self::aggregate_player_alterations(
                         uuid,
-                        damages,
-                        heals,
-                        *ranks.get(uuid).unwrap_or(&0u8),
+                        alterations,
+                        eliminations.get(uuid),
+                        *placements.get(&Placed::of(player)).unwrap_or(&0),
                         begin,
+                        settings,
                     ),
                 )
             })
@@ -138,11 +192,14 @@ impl Aggregate {
 
     fn aggregate_player_alterations(
         player: &Uuid,
-        damages: &Vec<Damage>,
-        heals: &Vec<Heal>,
-        rank: u8,
+        alterations: &Alterations,
+        elimination: Option<&DateTime<FixedOffset>>,
+        rank: u32,
         begin: &DateTime<FixedOffset>,
+        settings: &SettingsDamages,
     ) -> PlayerAlterationsAggregate {
+        let Alterations { damages, hits, heals } = *alterations;
+
         let mut damages_taken: Vec<Damage> = damages
             .iter()
             .cloned()
@@ -166,7 +223,21 @@ impl Aggregate {
         damages_caused.sort_by_key(|d| d.date);
         heals.sort_by_key(|h| h.date);
 
-        PlayerAlterationsAggregate {
+        let hits_taken: Vec<&Damage> = hits
+            .iter()
+            .filter(|hit| &hit.damagee.uuid == player)
+            .collect();
+
+        let health_timeline = Self::health_timeline(&hits_taken, &heals, settings);
+        let mut weapons = Self::aggregate_weapons(&damages_caused);
+        weapons.sort_by(|a, b| {
+            b.hits.cmp(&a.hits)
+                .then_with(|| b.damages.cmp(&a.damages))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        let deaths = damages_taken.iter().filter(|damage| damage.lethal).count() as u32;
+
+        let mut aggregate = PlayerAlterationsAggregate {
             damages_taken_total: (&damages_taken)
                 .iter()
                 .fold(0u32, |acc, damage| damage.damage as u32 + acc),
@@ -176,32 +247,74 @@ impl Aggregate {
             heals_total: (&heals)
                 .iter()
                 .fold(0u32, |acc, heal| heal.heal as u32 + acc),
+            friendly_fire_taken_total: damages_taken
+                .iter()
+                .filter(|damage| damage.friendly_fire)
+                .fold(0u32, |acc, damage| damage.damage as u32 + acc),
+            friendly_fire_caused_total: damages_caused
+                .iter()
+                .filter(|damage| damage.friendly_fire)
+                .fold(0u32, |acc, damage| damage.damage as u32 + acc),
+            team_kills: damages
+                .iter()
+                .filter(|damage| damage.lethal && &damage.damagee.uuid != player)
+                .filter(|damage| match damage.credited_player() {
+                    Some(cause) => {
+                        &cause.player.uuid == player && cause.player.team.is_some()
+                            && cause.player.team == damage.damagee.team
+                    }
+                    None => false
+                })
+                .count() as u32,
 
             damages_taken,
             damages_caused,
             heals,
+            health_timeline,
+            weapons,
 
             kills: damages
                 .iter()
-                .filter(|damage| match &damage.cause {
-                    DamageCause::Player(cause) => &cause.player.uuid == player && damage.lethal,
-                    _ => false
+                .filter(|damage| damage.lethal)
+                .filter(|damage| match damage.credited_player() {
+                    Some(cause) => &cause.player.uuid == player,
+                    None => false
                 })
                 .map(|damage| damage.damagee.clone())
                 .collect(),
 
+            assists: damages
+                .iter()
+                .filter(|damage| damage.assists.iter().any(|assist| &assist.player.uuid == player))
+                .map(|damage| damage.damagee.clone())
+                .collect(),
+
             killed_by: damages
                 .iter()
                 .filter(|damage| &damage.damagee.uuid == player && damage.lethal)
-                .map(|damage| damage.cause.clone())
+                .map(|damage| match &damage.attributed_to {
+                    Some(cause) => DamageCause::Player(cause.clone()),
+                    None => damage.cause.clone(),
+                })
                 .last(),
 
-            game_duration: match damages
+            assisted_by: damages
                 .iter()
                 .filter(|damage| &damage.damagee.uuid == player && damage.lethal)
-                .map(|damage| &damage.date)
-                .last()
-            {
+                .map(|damage| damage.assists.clone())
+                .next_back()
+                .unwrap_or_default(),
+
+            death_cause: damages
+                .iter()
+                .filter(|damage| &damage.damagee.uuid == player && damage.lethal)
+                .map(|damage| damage.cause.clone())
+                .next_back(),
+
+            deaths: Some(deaths),
+            eliminated_at: elimination.map(|date| since(date, begin)),
+
+            game_duration: match elimination {
                 Some(date) => since(date, begin),
                 None => damages
                     .iter()
@@ -211,7 +324,323 @@ impl Aggregate {
             },
 
             rank,
+            kills_deaths_ratio: 0.0,
+
+            first_blood: false,
+            longest_kill_streak: 0,
+            multi_kills: vec![],
+        };
+
+        aggregate.kills_deaths_ratio = aggregate.kills.len() as f32 / deaths.max(1) as f32;
+
+        aggregate
+    }
+
+    /// Replays the hits taken (not grouped, so heals between two hits from the same cause are
+    /// applied in between) and heals received by a player, both sorted chronologically, to
+    /// compute their health after each of them, starting with full health and the configured
+    /// absorption (and again after each death). Damages are absorbed by the absorption first. Only
+    /// changes are sampled.
+    fn health_timeline(
+        hits_taken: &[&Damage],
+        heals: &[Heal],
+        settings: &SettingsDamages,
+    ) -> Vec<HealthPoint> {
+        enum Alteration<'a> {
+            Damage(&'a Damage),
+            Heal(&'a Heal),
+        }
+
+        let mut alterations: Vec<(Duration, Alteration)> = hits_taken
+            .iter()
+            .map(|hit| (hit.since_beginning, Alteration::Damage(hit)))
+            .chain(heals.iter().map(|heal| (heal.since_beginning, Alteration::Heal(heal))))
+            .collect();
+
+        // Stable sort: at the same time, damages are applied before heals.
+        alterations.sort_by_key(|(since_beginning, _)| *since_beginning);
+
+        let mut health = settings.max_health;
+        let mut absorption = settings.absorption;
+
+        let mut timeline = vec![HealthPoint {
+            since_beginning: Duration::from_secs(0),
+            health,
+            absorption,
+        }];
+
+        let mut dead = false;
+
+        for (since_beginning, alteration) in alterations {
+            // In game modes with several lives, a dead player respawns with full health.
+            if dead {
+                health = settings.max_health;
+                absorption = settings.absorption;
+                dead = false;
+            }
+
+            match alteration {
+                Alteration::Damage(damage) if damage.lethal => {
+                    health = 0;
+                    absorption = 0;
+                    dead = true;
+                }
+                Alteration::Damage(damage) => {
+                    let absorbed = damage.damage.min(absorption);
+                    absorption -= absorbed;
+                    health = health.saturating_sub(damage.damage - absorbed);
+                }
+                Alteration::Heal(heal) => {
+                    health = health.saturating_add(heal.heal).min(settings.max_health);
+                }
+            }
+
+            match timeline.last_mut() {
+                Some(last) if last.health == health && last.absorption == absorption => {}
+                Some(last) if last.since_beginning == since_beginning => {
+                    last.health = health;
+                    last.absorption = absorption;
+                }
+                _ => timeline.push(HealthPoint {
+                    since_beginning,
+                    health,
+                    absorption,
+                }),
+            }
         }
+
+        timeline
+    }
+
+    /// Computes the placement of every player, or team for players in teams: players (or teams)
+    /// still alive at the end (or never eliminated, depending on the game mode) share the first
+    /// rank, then others are ranked by decreasing elimination date (the last death of a team
+    /// member, for teams). Players (or teams) eliminated at the same time share the same rank.
+    ///
+    /// In `GameMode::Respawn`, no one is ever eliminated, so everyone is ranked first.
+    fn placements(
+        players: &HashMap<Uuid, Rc<Player>>,
+        players_eliminations: &HashMap<Uuid, DateTime<FixedOffset>>,
+    ) -> BTreeMap<Placed, u32> {
+        // For each player or team, its elimination date, or `None` if it is still alive.
+        let mut eliminations: BTreeMap<Placed, Option<DateTime<FixedOffset>>> = BTreeMap::new();
+        for (uuid, player) in players {
+            let death = players_eliminations.get(uuid).cloned();
+
+            eliminations
+                .entry(Placed::of(player))
+                .and_modify(|elimination| {
+                    *elimination = match (*elimination, death) {
+                        (Some(elimination), Some(death)) => Some(elimination.max(death)),
+                        _ => None,
+                    }
+                })
+                .or_insert(death);
+        }
+
+        fn is_placed_before(
+            a: &Option<DateTime<FixedOffset>>,
+            b: &Option<DateTime<FixedOffset>>,
+        ) -> bool {
+            match (a, b) {
+                (None, Some(_)) => true,
+                (Some(a), Some(b)) => a > b,
+                _ => false,
+            }
+        }
+
+        eliminations
+            .iter()
+            .map(|(placed, elimination)| {
+                let before = eliminations
+                    .values()
+                    .filter(|other| is_placed_before(other, elimination))
+                    .count();
+
+                (placed.clone(), before as u32 + 1)
+            })
+            .collect()
+    }
+
+    /// Computes what each player (or group of players, identified by `key`; players without key
+    /// are ignored) did to the others. Players hitting or killing themselves are ignored.
+    fn aggregate_head_to_head<K: Ord>(
+        damages: &[Damage],
+        key: impl Fn(&SimplePlayer) -> Option<K>,
+    ) -> BTreeMap<K, BTreeMap<K, HeadToHead>> {
+        let mut head_to_head: BTreeMap<K, BTreeMap<K, HeadToHead>> = BTreeMap::new();
+
+        fn pair<K: Ord>(
+            head_to_head: &mut BTreeMap<K, BTreeMap<K, HeadToHead>>,
+            attacker: Option<K>,
+            victim: Option<K>,
+        ) -> Option<&mut HeadToHead> {
+            let (attacker, victim) = (attacker?, victim?);
+            Some(head_to_head.entry(attacker).or_default().entry(victim).or_default())
+        }
+
+        for damage in damages {
+            let victim = &damage.damagee;
+
+            if let DamageCause::Player(cause) = &damage.cause {
+                if &cause.player != victim {
+                    if let Some(pair) = pair(&mut head_to_head, key(&cause.player), key(victim)) {
+                        pair.damages += damage.damage as u32;
+                        pair.hits += damage.hits;
+                    }
+                }
+            }
+
+            if let Some(killer) = damage.credited_player().filter(|_| damage.lethal) {
+                if &killer.player != victim {
+                    if let Some(pair) = pair(&mut head_to_head, key(&killer.player), key(victim)) {
+                        pair.kills += 1;
+                    }
+                }
+            }
+        }
+
+        head_to_head
+    }
+
+    /// Computes the damages, hits and kills made with each weapon (identified by its item ID and
+    /// its enchantments, whatever their order), by players or entities. Damages made bare-handed
+    /// are ignored.
+    fn aggregate_weapons(damages: &[Damage]) -> Vec<WeaponAggregate> {
+        let mut weapons: BTreeMap<(String, Vec<(String, u32)>), WeaponAggregate> = BTreeMap::new();
+
+        for damage in damages {
+            let (weapon, suicide) = match &damage.cause {
+                DamageCause::Player(cause) => (&cause.weapon, cause.player == damage.damagee),
+                DamageCause::Entity(cause) => (&cause.weapon, false),
+                _ => continue,
+            };
+
+            let weapon = match weapon {
+                Some(weapon) => weapon,
+                None => continue,
+            };
+
+            let mut enchantments: Vec<EnchantmentTag> = weapon
+                .tag
+                .as_ref()
+                .and_then(|tag| tag.enchantments.clone())
+                .unwrap_or_default();
+            enchantments.sort_by(|a, b| a.id.cmp(&b.id).then_with(|| a.lvl.cmp(&b.lvl)));
+
+            let key = (
+                weapon.id.clone(),
+                enchantments.iter().map(|e| (e.id.clone(), e.lvl)).collect(),
+            );
+
+            let aggregate = weapons.entry(key).or_insert_with(|| WeaponAggregate {
+                id: weapon.id.clone(),
+                enchantments,
+                item: weapon.clone(),
+                damages: 0,
+                hits: 0,
+                kills: 0,
+            });
+
+            aggregate.damages += damage.damage as u32;
+            aggregate.hits += damage.hits;
+            if damage.lethal && !suicide {
+                aggregate.kills += 1;
+            }
+        }
+
+        weapons.into_values().collect()
+    }
+
+    fn aggregate_teams_alterations(
+        teams: &[Team],
+        damages: &[Damage],
+        heals: &[Heal],
+        eliminations: &HashMap<Uuid, DateTime<FixedOffset>>,
+        placements: &BTreeMap<Placed, u32>,
+    ) -> BTreeMap<String, TeamAlterationsAggregate> {
+        teams
+            .iter()
+            .map(|team| {
+                (
+                    team.name.clone(),
+                    Self::aggregate_team_alterations(
+                        team,
+                        damages,
+                        heals,
+                        eliminations,
+                        *placements.get(&Placed::Team(team.name.clone())).unwrap_or(&0),
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    fn aggregate_team_alterations(
+        team: &Team,
+        damages: &[Damage],
+        heals: &[Heal],
+        eliminations: &HashMap<Uuid, DateTime<FixedOffset>>,
+        rank: u32,
+    ) -> TeamAlterationsAggregate {
+        let in_team = |player: &SimplePlayer| player.team.as_ref() == Some(&team.name);
+
+        let mut aggregate = TeamAlterationsAggregate {
+            damages_taken_total: 0,
+            damages_caused_total: 0,
+            friendly_fire_total: 0,
+            heals_total: heals
+                .iter()
+                .filter(|heal| in_team(&heal.healed))
+                .fold(0u32, |acc, heal| heal.heal as u32 + acc),
+            kills: 0,
+            team_kills: 0,
+            deaths: 0,
+            eliminated_at: None,
+            rank,
+        };
+
+        for damage in damages {
+            let damager_in_team = match &damage.cause {
+                DamageCause::Player(cause) => in_team(&cause.player),
+                _ => false,
+            };
+
+            match (damager_in_team, in_team(&damage.damagee)) {
+                (true, true) => {
+                    if damage.friendly_fire {
+                        aggregate.friendly_fire_total += damage.damage as u32;
+                    }
+                    aggregate.damages_taken_total += damage.damage as u32;
+                }
+                (true, false) => aggregate.damages_caused_total += damage.damage as u32,
+                (false, true) => aggregate.damages_taken_total += damage.damage as u32,
+                (false, false) => {}
+            }
+
+            if let Some(killer) = damage.credited_player().filter(|_| damage.lethal) {
+                match (in_team(&killer.player), in_team(&damage.damagee)) {
+                    (true, true) if killer.player != damage.damagee => aggregate.team_kills += 1,
+                    (true, false) => aggregate.kills += 1,
+                    _ => {}
+                }
+            }
+
+            if damage.lethal && in_team(&damage.damagee) {
+                aggregate.deaths += 1;
+
+                // The team is eliminated when its last alive member is.
+                let eliminated = team.players.iter().all(|player| {
+                    matches!(eliminations.get(&player.uuid), Some(date) if date <= &damage.date)
+                });
+
+                if eliminated {
+                    aggregate.eliminated_at.get_or_insert(damage.since_beginning);
+                }
+            }
+        }
+
+        aggregate
     }
 
     fn aggregate_environmental_damages(damages: &Vec<Damage>) -> EnvironmentalDamagesAggregate {
@@ -247,7 +676,7 @@ impl Aggregate {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct PlayerAlterationsAggregate {
     pub damages_taken: Vec<Damage>,
     pub damages_taken_total: u32,
@@ -255,13 +684,189 @@ pub struct PlayerAlterationsAggregate {
     pub damages_caused_total: u32,
     pub heals: Vec<Heal>,
     pub heals_total: u32,
+    #[serde(default)]
+    pub health_timeline: Vec<HealthPoint>,
+
+    /// The weapons used by this player, sorted by decreasing hits: the first one is their
+    /// favourite weapon.
+    #[serde(default)]
+    pub weapons: Vec<WeaponAggregate>,
+
+    #[serde(default)]
+    pub friendly_fire_taken_total: u32,
+    #[serde(default)]
+    pub friendly_fire_caused_total: u32,
+
+    /// The players killed by this player, including their teammates (unlike
+    /// `TeamAlterationsAggregate::kills`), who are also counted in `team_kills`.
     pub kills: Vec<SimplePlayer>,
+    #[serde(default)]
+    pub team_kills: u32,
+
+    /// The players killed by someone else with the help of this player.
+    #[serde(default)]
+    pub assists: Vec<SimplePlayer>,
+
+    /// The cause of the death, or the player credited for it (see
+    /// `SettingsDamages::kill_attribution_window`).
     pub killed_by: Option<DamageCause>,
+
+    /// The players who helped to kill this player.
+    #[serde(default)]
+    pub assisted_by: Vec<Assist>,
+
+    /// The actual cause of the lethal damage, even if the kill was credited to a player.
+    #[serde(default)]
+    pub death_cause: Option<DamageCause>,
+
+    /// How many times this player died, and when they were eliminated, if they were (see
+    /// `settings::GameMode`). Reports processed before deaths were counted have no `deaths`: use
+    /// `PlayerAlterationsAggregate::deaths`.
+    #[serde(default)]
+    pub deaths: Option<u32>,
+    #[serde(default)]
+    pub eliminated_at: Option<Duration>,
+
+    /// Kills per death (or kills, if the player never died).
+    #[serde(default)]
+    pub kills_deaths_ratio: f32,
+
     pub game_duration: Duration,
-    pub rank: u8,
+
+    /// The placement of the player (or of their team) in the game; see `Aggregate::placements`.
+    #[serde(default)]
+    pub rank: u32,
+
+    /// `true` if this player made the first kill of the game.
+    #[serde(default)]
+    pub first_blood: bool,
+    #[serde(default)]
+    pub longest_kill_streak: u32,
+    #[serde(default)]
+    pub multi_kills: Vec<MultiKill>,
+}
+
+/// Computes when each player was eliminated, for players who were: it depends on the number of
+/// lives the game mode gives them. Damages are expected to be sorted chronologically, and not
+/// grouped, so that eliminations are dated by the lethal hit.
+pub fn eliminations(
+    damages: &[Damage],
+    game_mode: GameMode,
+) -> HashMap<Uuid, DateTime<FixedOffset>> {
+    let mut deaths: HashMap<Uuid, u32> = HashMap::new();
+    let mut eliminations = HashMap::new();
+
+    if let Some(lives) = game_mode.lives() {
+        for damage in damages.iter().filter(|damage| damage.lethal) {
+            let player_deaths = deaths.entry(damage.damagee.uuid).or_insert(0);
+            *player_deaths += 1;
+
+            if *player_deaths == lives {
+                eliminations.insert(damage.damagee.uuid, damage.date);
+            }
+        }
+    }
+
+    eliminations
+}
+
+/// The damages and heals of a game, given together to the aggregations of each player.
+struct Alterations<'a> {
+    /// The damages, grouped (see `Damage::group`).
+    damages: &'a [Damage],
+
+    /// The same damages, not grouped.
+    hits: &'a [Damage],
+
+    heals: &'a [Heal],
+}
+
+/// Something placed in the game: a team, or a player without team.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Placed {
+    Team(String),
+    Player(Uuid),
+}
+
+impl Placed {
+    fn of(player: &Player) -> Self {
+        match &player.team {
+            Some(team) => Placed::Team(team.clone()),
+            None => Placed::Player(player.uuid),
+        }
+    }
+}
+
+/// The health of a player at some point of the game, in half-hearts.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct HealthPoint {
+    pub since_beginning: Duration,
+    pub health: u16,
+    pub absorption: u16,
+}
+
+impl PlayerAlterationsAggregate {
+    /// How many times this player died. For reports processed before deaths were counted, players
+    /// could only die once, so this is whether they were killed.
+    pub fn deaths(&self) -> u32 {
+        self.deaths.unwrap_or(self.killed_by.is_some() as u32)
+    }
+
+    fn apply_highlights(&mut self, highlights: &PlayerHighlights) {
+        self.first_blood = highlights.first_blood.is_some();
+        self.longest_kill_streak = highlights
+            .longest_kill_streak
+            .as_ref()
+            .map_or(0, |streak| streak.kills);
+        self.multi_kills = highlights.multi_kills.clone();
+    }
+}
+
+/// Damages and heals of a whole team. Damages made by a member of the team to another are counted
+/// as friendly fire (and as damages taken), not as damages caused; and such kills as team kills.
+///
+/// The team is eliminated when the last of its members is eliminated.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct TeamAlterationsAggregate {
+    pub damages_taken_total: u32,
+    pub damages_caused_total: u32,
+    pub friendly_fire_total: u32,
+    pub heals_total: u32,
+
+    /// The players of other teams killed by members of this team. Members killed by their
+    /// teammates are only counted in `team_kills`.
+    pub kills: u32,
+    pub team_kills: u32,
+    pub deaths: u32,
+    pub eliminated_at: Option<Duration>,
+    pub rank: u32,
+}
+
+/// What was done with a weapon: an item ID with a set of enchantments.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct WeaponAggregate {
+    pub id: String,
+
+    /// The enchantments of the weapon, sorted by ID.
+    pub enchantments: Vec<EnchantmentTag>,
+
+    /// The first item seen for this weapon, e.g. to display it with its name.
+    pub item: Item,
+
+    pub damages: u32,
+    pub hits: u32,
+    pub kills: u32,
+}
+
+/// What a player (or team) did to another one.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeadToHead {
+    pub damages: u32,
+    pub hits: u32,
+    pub kills: u32,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct EnvironmentalDamagesAggregate {
     pub entities: BTreeMap<String, u32>,
     pub environment: BTreeMap<String, u32>
@@ -12,9 +12,16 @@ use crate::report::player::{Player, SimplePlayer};
 use crate::report::raw::Damage as RawDamage;
 use crate::report::raw::DamageCause as RawDamageCause;
 use crate::report::report::since;
+use crate::report::settings::SettingsDamages;
+
+/// Damages processed before hits were counted are considered as a single hit.
+#[inline(always)]
+fn default_hits() -> u32 {
+    1
+}
 
 /// Some damage made to a player, represented as a single line on the web interface.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Damage {
     pub date: DateTime<FixedOffset>,
     pub since_beginning: Duration,
@@ -22,11 +29,29 @@ pub struct Damage {
     pub damagee: SimplePlayer,
     pub damage: u16,
     pub lethal: bool,
+
+    /// The number of hits grouped into this damage.
+    #[serde(default = "default_hits")]
+    pub hits: u32,
+
+    /// `true` if the damage was caused by a player to one of their teammates.
+    #[serde(default)]
+    pub friendly_fire: bool,
+
+    /// For lethal damages from the environment, the last player who hit the damagee shortly
+    /// before, to whom the kill is credited.
+    #[serde(default)]
+    pub attributed_to: Option<PlayerDamageCause>,
+
+    /// For lethal damages, the players (other than the killer) who damaged the damagee shortly
+    /// before, sorted by decreasing damage.
+    #[serde(default)]
+    pub assists: Vec<Assist>,
 }
 
 impl Damage {
     /// Converts raw damages to processed ones, individually. The grouping is done in the
-    /// `from_raw_vec` method.
+    /// `group` method.
     pub fn from_raw(
         raw_damage: RawDamage,
         players: &HashMap<Uuid, Rc<Player>>,
@@ -39,13 +64,20 @@ impl Damage {
                     uuid: raw_damage.damagee,
                 })?;
 
+        let cause = DamageCause::from_raw(raw_damage.cause, players)?;
+        let damagee: SimplePlayer = (*damagee).as_ref().into();
+
         Ok(Self {
             date: raw_damage.date,
             since_beginning: since(&raw_damage.date, begin),
-            cause: DamageCause::from_raw(raw_damage.cause, players)?,
-            damagee: (*damagee).as_ref().into(),
+            friendly_fire: cause.is_friendly_fire_against(&damagee),
+            cause,
+            damagee,
             damage: raw_damage.damage,
             lethal: raw_damage.lethal,
+            hits: 1,
+            attributed_to: None,
+            assists: vec![],
         })
     }
 
@@ -59,27 +91,89 @@ impl Damage {
     /// but not the date, so the other damage should be posterior.
     pub fn merge_with(&mut self, other: &Damage) {
         self.damage += other.damage;
+        self.hits += other.hits;
         // If the new damage is lethal, so is the previous one grouped with the new.
         self.lethal = other.lethal;
+        self.attributed_to = other.attributed_to.clone();
+        self.assists = other.assists.clone();
     }
 
-    /// From a vec of raw damages, extract a vec of processed and grouped damages.
-    /// Damages are grouped together if they are between the same players, or between a player and
-    /// the same entity type, or of the same type; with the same properties (exact same weapon,
-    /// etc.); and consecutive.
+    /// Returns the player credited for this damage: the damager if it is a player, or the player
+    /// the damage is attributed to if it was caused by the environment.
+    pub fn credited_player(&self) -> Option<&PlayerDamageCause> {
+        match &self.cause {
+            DamageCause::Player(cause) => Some(cause),
+            _ => self.attributed_to.as_ref(),
+        }
+    }
+
+    /// From a vec of raw damages, extract a vec of processed damages, not grouped yet (see
+    /// `group`), sorted chronologically.
+    ///
+    /// Lethal damages from the environment a player can be pushed into (see
+    /// `DamageCause::is_indirect_kill`) are attributed to the last player who hit the damagee, if
+    /// they did so in the kill attribution window; and other players who hit the damagee in the
+    /// assist window are credited with an assist.
     ///
     /// The given vec of raw damages is **expected to be sorted chronologically**.
     pub fn from_raw_vec(
         raw_damages: Vec<RawDamage>,
         players: &HashMap<Uuid, Rc<Player>>,
         begin: &DateTime<FixedOffset>,
+        settings: &SettingsDamages,
     ) -> ReportResult<Vec<Self>> {
-        let mut previous_damages = Vec::new();
-        let mut latest_damage_per_damagee: HashMap<Uuid, Damage> = HashMap::new();
+        let mut damages = Vec::new();
+        // Players hits received by each player since their last death, used to attribute kills
+        // and to compute assists.
+        let mut attacks_per_damagee: HashMap<Uuid, Vec<Attack>> = HashMap::new();
+
+        let attribution_window = chrono::Duration::seconds(settings.kill_attribution_window as i64);
+        let assist_window = chrono::Duration::seconds(settings.assist_window as i64);
 
         for damage in raw_damages {
-            let damage = Self::from_raw(damage, players, begin)?;
+            let mut damage = Self::from_raw(damage, players, begin)?;
+
+            let attacks = attacks_per_damagee.entry(damage.damagee.uuid).or_default();
+
+            match &damage.cause {
+                DamageCause::Player(cause) if cause.player != damage.damagee => {
+                    attacks.push((damage.date, cause.clone(), damage.damage));
+                }
+                cause if damage.lethal
+                    && cause.is_indirect_kill()
+                    && settings.kill_attribution_window > 0 =>
+                {
+                    damage.attributed_to = attacks
+                        .last()
+                        .filter(|(date, _, _)| damage.date - *date <= attribution_window)
+                        .map(|(_, cause, _)| cause.clone());
+                }
+                _ => {}
+            }
+
+            if damage.lethal {
+                if settings.assist_window > 0 {
+                    damage.assists = Assist::from_attacks(&damage, attacks, assist_window);
+                }
+
+                attacks.clear();
+            }
+
+            damages.push(damage);
+        }
+
+        Ok(damages)
+    }
+
+    /// Groups processed damages (sorted chronologically), to display them as single lines.
+    /// Damages are grouped together if they are between the same players, or between a player and
+    /// the same entity type, or of the same type; with the same properties (exact same weapon,
+    /// etc.); and consecutive.
+    pub fn group(damages: Vec<Self>) -> Vec<Self> {
+        let mut previous_damages = Vec::new();
+        let mut latest_damage_per_damagee: HashMap<Uuid, Damage> = HashMap::new();
 
+        for damage in damages {
             // If the previously recorded damage is the same (same type, same damager if any, same
             // weapon), we merge them.
             match latest_damage_per_damagee.get_mut(&damage.damagee.uuid) {
@@ -104,12 +198,77 @@ impl Damage {
         // are correctly sorted.
         damages.sort_by_key(|d| d.date);
 
-        Ok(damages)
+        damages
+    }
+}
+
+/// A hit from a player: when, by whom, and how much damage was dealt.
+type Attack = (DateTime<FixedOffset>, PlayerDamageCause, u16);
+
+/// A player who helped to kill another, without dealing the final blow.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct Assist {
+    pub player: SimplePlayer,
+    pub damage: u32,
+
+    /// The percentage of the damages the victim received from players in the assist window
+    /// (including the killer's) that were dealt by this player; `0` if they only received hits
+    /// without damage.
+    pub share: f32,
+}
+
+impl Assist {
+    /// Computes the assists of a lethal damage from the players hits the victim received since
+    /// their last death (in chronological order).
+    fn from_attacks(
+        lethal_damage: &Damage,
+        attacks: &[Attack],
+        window: chrono::Duration,
+    ) -> Vec<Self> {
+        let killer = lethal_damage.credited_player().map(|cause| &cause.player);
+
+        let mut damages_per_player: Vec<(&SimplePlayer, u32)> = Vec::new();
+        let mut total = 0u32;
+
+        for (_, cause, damage) in attacks
+            .iter()
+            .filter(|(date, _, _)| lethal_damage.date - *date <= window)
+        {
+            total += *damage as u32;
+
+            match damages_per_player
+                .iter_mut()
+                .find(|(player, _)| *player == &cause.player)
+            {
+                Some((_, player_damage)) => *player_damage += *damage as u32,
+                None => damages_per_player.push((&cause.player, *damage as u32)),
+            }
+        }
+
+        let mut assists: Vec<Self> = damages_per_player
+            .into_iter()
+            .filter(|(player, _)| Some(*player) != killer)
+            .map(|(player, damage)| Assist {
+                player: player.clone(),
+                damage,
+                share: match total {
+                    0 => 0.0,
+                    total => damage as f32 * 100.0 / total as f32,
+                },
+            })
+            .collect();
+
+        assists.sort_by(|a, b| {
+            b.damage
+                .cmp(&a.damage)
+                .then_with(|| a.player.name.cmp(&b.player.name))
+        });
+        assists
     }
 }
 
 /// Represents what caused a damage, including metadata (like player or entity, and weapon) if any.
-#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash, Display)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Eq, PartialEq, Hash, Display)]
 #[serde(rename_all = "snake_case", tag = "type")]
 #[strum(serialize_all = "snake_case")]
 pub enum DamageCause {
@@ -145,13 +304,17 @@ pub enum DamageCause {
     Unknown,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Eq, PartialEq, Hash)]
 pub struct EntityDamageCause {
     pub entity: String,
+
+    /// The weapon, either as JSON or as an SNBT string in raw reports.
+    #[serde(default, deserialize_with = "crate::report::item::snbt::deserialize_item")]
+    #[schemars(schema_with = "crate::report::item::snbt::item_schema")]
     pub weapon: Option<Item>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Eq, PartialEq, Hash)]
 pub struct PlayerDamageCause {
     pub player: SimplePlayer,
     pub weapon: Option<Item>,
@@ -168,6 +331,33 @@ impl DamageCause {
         }
     }
 
+    /// Checks if this damage cause is something a player can be pushed or knocked into by another
+    /// one: a fall, the void, lava, fire or water. Deaths from other causes (e.g. `Command` or
+    /// `Suicide`) are never credited to a player.
+    pub fn is_indirect_kill(&self) -> bool {
+        matches!(
+            self,
+            DamageCause::Fall
+                | DamageCause::Void
+                | DamageCause::Lava
+                | DamageCause::Fire
+                | DamageCause::Drowning
+        )
+    }
+
+    /// Checks if this damage cause is a player from the same team as the given damagee. Players
+    /// without team have no teammates, and players damaging themselves are not counted.
+    pub fn is_friendly_fire_against(&self, damagee: &SimplePlayer) -> bool {
+        match self {
+            DamageCause::Player(cause) => {
+                cause.player.uuid != damagee.uuid
+                    && cause.player.team.is_some()
+                    && cause.player.team == damagee.team
+            }
+            _ => false,
+        }
+    }
+
     /// Converts a raw damage cause to a processed one. Players UUIDs are replaced by simple players
     /// references, and `Fire` and `FireTick` are merged together.
     pub fn from_raw(
@@ -0,0 +1,264 @@
+//! Compares two processed reports of the same game, e.g. to check what a plugin update changed in
+//! its output.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::report::aggregates::PlayerAlterationsAggregate;
+use crate::report::errors::{InvalidReportError, ReportResult};
+use crate::report::player::{Player, PlayerStatistics, SimplePlayer};
+use crate::report::report::Report;
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct Change<T> {
+    pub before: T,
+    pub after: T,
+}
+
+impl<T: PartialEq> Change<T> {
+    fn of(before: T, after: T) -> Option<Self> {
+        match before == after {
+            true => None,
+            false => Some(Change { before, after }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct StatisticChange {
+    /// The statistics group: `generic`, `used`, `mined` or `picked_up`.
+    pub group: String,
+    pub statistic: String,
+    pub before: Option<u32>,
+    pub after: Option<u32>,
+}
+
+/// The changes of a player present in both reports. Unchanged values are `None`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct PlayerDiff {
+    pub player: SimplePlayer,
+    pub statistics: Vec<StatisticChange>,
+    pub damages_taken_total: Option<Change<u32>>,
+    pub damages_caused_total: Option<Change<u32>>,
+    pub heals_total: Option<Change<u32>>,
+    pub kills: Option<Change<u32>>,
+    pub deaths: Option<Change<u32>>,
+    pub rank: Option<Change<u32>>,
+}
+
+impl PlayerDiff {
+    pub fn is_empty(&self) -> bool {
+        self.statistics.is_empty()
+            && self.damages_taken_total.is_none()
+            && self.damages_caused_total.is_none()
+            && self.heals_total.is_none()
+            && self.kills.is_none()
+            && self.deaths.is_none()
+            && self.rank.is_none()
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct ReportDiff {
+    pub players_added: Vec<SimplePlayer>,
+    pub players_removed: Vec<SimplePlayer>,
+
+    /// The players present in both reports with changes, sorted by name.
+    pub players_changed: Vec<PlayerDiff>,
+}
+
+impl ReportDiff {
+    /// Compares two processed reports, which must share the same `match_uuid`. Players are
+    /// matched by UUID.
+    pub fn between(before: &Report, after: &Report) -> ReportResult<Self> {
+        if before.match_uuid != after.match_uuid {
+            return Err(InvalidReportError::MismatchedMatchUuid {
+                expected: before.match_uuid,
+                found: after.match_uuid,
+            });
+        }
+
+        let find = |report: &Report, player: &Player| -> bool {
+            report.players.iter().any(|p| p.uuid == player.uuid)
+        };
+
+        let players_added = after
+            .players
+            .iter()
+            .filter(|player| !find(before, player))
+            .map(|player| player.clone().into())
+            .collect();
+
+        let players_removed = before
+            .players
+            .iter()
+            .filter(|player| !find(after, player))
+            .map(|player| player.clone().into())
+            .collect();
+
+        let mut players_changed: Vec<PlayerDiff> = after
+            .players
+            .iter()
+            .filter_map(|player| {
+                let previous = before.players.iter().find(|p| p.uuid == player.uuid)?;
+
+                let diff = Self::player_diff(
+                    previous,
+                    before.aggregates.players_damages.get(&player.uuid),
+                    player,
+                    after.aggregates.players_damages.get(&player.uuid),
+                );
+
+                match diff.is_empty() {
+                    true => None,
+                    false => Some(diff),
+                }
+            })
+            .collect();
+
+        players_changed.sort_by(|a, b| a.player.name.cmp(&b.player.name));
+
+        Ok(ReportDiff {
+            players_added,
+            players_removed,
+            players_changed,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.players_added.is_empty()
+            && self.players_removed.is_empty()
+            && self.players_changed.is_empty()
+    }
+
+    fn player_diff(
+        before: &Player,
+        before_aggregate: Option<&PlayerAlterationsAggregate>,
+        after: &Player,
+        after_aggregate: Option<&PlayerAlterationsAggregate>,
+    ) -> PlayerDiff {
+        let value = |aggregate: Option<&PlayerAlterationsAggregate>,
+                     extract: fn(&PlayerAlterationsAggregate) -> u32| {
+            aggregate.map(extract).unwrap_or(0)
+        };
+
+        let change = |extract: fn(&PlayerAlterationsAggregate) -> u32| {
+            Change::of(
+                value(before_aggregate, extract),
+                value(after_aggregate, extract),
+            )
+        };
+
+        PlayerDiff {
+            player: after.clone().into(),
+            statistics: Self::statistics_diff(&before.statistics, &after.statistics),
+            damages_taken_total: change(|a| a.damages_taken_total),
+            damages_caused_total: change(|a| a.damages_caused_total),
+            heals_total: change(|a| a.heals_total),
+            kills: change(|a| a.kills.len() as u32),
+            deaths: change(|a| a.deaths()),
+            rank: change(|a| a.rank),
+        }
+    }
+
+    fn statistics_diff(
+        before: &Option<PlayerStatistics>,
+        after: &Option<PlayerStatistics>,
+    ) -> Vec<StatisticChange> {
+        fn groups(
+            statistics: &Option<PlayerStatistics>,
+        ) -> Vec<(&'static str, Option<&BTreeMap<String, u32>>)> {
+            let statistics = statistics.as_ref();
+
+            vec![
+                ("generic", statistics.and_then(|s| s.generic.as_ref())),
+                ("used", statistics.and_then(|s| s.used.as_ref())),
+                ("mined", statistics.and_then(|s| s.mined.as_ref())),
+                ("picked_up", statistics.and_then(|s| s.picked_up.as_ref())),
+            ]
+        }
+
+        groups(before)
+            .into_iter()
+            .zip(groups(after))
+            .flat_map(|((group, before), (_, after))| {
+                let names: BTreeSet<&String> = before
+                    .into_iter()
+                    .chain(after)
+                    .flat_map(|statistics| statistics.keys())
+                    .collect();
+
+                names
+                    .into_iter()
+                    .filter_map(|name| {
+                        let before = before.and_then(|s| s.get(name)).copied();
+                        let after = after.and_then(|s| s.get(name)).copied();
+
+                        match before == after {
+                            true => None,
+                            false => Some(StatisticChange {
+                                group: group.to_string(),
+                                statistic: name.clone(),
+                                before,
+                                after,
+                            }),
+                        }
+                    })
+                    .collect::<Vec<StatisticChange>>()
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for ReportDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "The reports are identical.");
+        }
+
+        for player in &self.players_added {
+            writeln!(f, "+ {} ({})", player.name, player.uuid)?;
+        }
+
+        for player in &self.players_removed {
+            writeln!(f, "- {} ({})", player.name, player.uuid)?;
+        }
+
+        for diff in &self.players_changed {
+            writeln!(f, "~ {} ({})", diff.player.name, diff.player.uuid)?;
+
+            let totals = [
+                ("damages taken", &diff.damages_taken_total),
+                ("damages caused", &diff.damages_caused_total),
+                ("heals", &diff.heals_total),
+                ("kills", &diff.kills),
+                ("deaths", &diff.deaths),
+                ("rank", &diff.rank),
+            ];
+
+            for (name, change) in totals.iter() {
+                if let Some(change) = change {
+                    writeln!(f, "    {}: {} → {}", name, change.before, change.after)?;
+                }
+            }
+
+            for change in &diff.statistics {
+                let value = |value: Option<u32>| match value {
+                    Some(value) => value.to_string(),
+                    None => "none".to_string(),
+                };
+
+                writeln!(
+                    f,
+                    "    {} {}: {} → {}",
+                    change.group,
+                    change.statistic,
+                    value(change.before),
+                    value(change.after)
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
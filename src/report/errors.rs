@@ -1,5 +1,6 @@
 use std::result::Result;
 
+use chrono::{DateTime, FixedOffset};
 use uuid::Uuid;
 
 pub type ReportResult<T> = Result<T, InvalidReportError>;
@@ -12,6 +13,46 @@ pub enum InvalidReportError {
     )]
     MissingPlayerReference { uuid: Uuid },
 
+    #[fail(display = "This player UUID is used by several players: {}", uuid)]
+    DuplicatePlayer { uuid: Uuid },
+
+    #[fail(
+        display = "This player is in several teams ({} and {}): {}",
+        first_team, second_team, uuid
+    )]
+    PlayerInSeveralTeams {
+        uuid: Uuid,
+        first_team: String,
+        second_team: String,
+    },
+
+    #[fail(
+        display = "This date is before the beginning of the game ({}): {}",
+        begin, date
+    )]
+    DateBeforeBeginning {
+        date: DateTime<FixedOffset>,
+        begin: DateTime<FixedOffset>,
+    },
+
+    #[fail(display = "This report format version is not supported: {}", version)]
+    UnsupportedFormatVersion { version: u64 },
+
+    #[fail(display = "There is no report to merge")]
+    NothingToMerge,
+
+    #[fail(
+        display = "Only reports of the same match can be merged or compared (expected {}): {}",
+        expected, found
+    )]
+    MismatchedMatchUuid { expected: Uuid, found: Uuid },
+
+    #[fail(display = "Invalid SNBT item (at character {}): {}", position, reason)]
+    InvalidSnbt { position: usize, reason: String },
+
+    #[fail(display = "Invalid SNBT item (at {}): {}", path, reason)]
+    InvalidSnbtItem { path: String, reason: String },
+
     #[fail(display = "An unknown error happened")]
     Unknown,
 }
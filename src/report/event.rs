@@ -11,7 +11,7 @@ pub fn default_event_type() -> EventType {
     EventType::Blue
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Event {
     pub date: DateTime<FixedOffset>,
     pub since_beginning: Duration,
@@ -47,9 +47,38 @@ impl Event {
 
         events
     }
+
+    /// Merges generated events into the (chronologically sorted) events, keeping them sorted.
+    ///
+    /// A generated event is skipped if it duplicates an existing event (e.g. if the plugin already
+    /// emitted it): an event with the same date and title, or with the same date, type and player
+    /// icon (as titles may be written differently). Each existing event can only stand for one
+    /// generated event, so that e.g. the elimination of a team is kept along with the death of
+    /// its last member.
+    pub fn merge_generated(events: &mut Vec<Self>, generated: Vec<Self>) {
+        let mut matched = vec![false; events.len()];
+
+        for event in generated {
+            let duplicate = events[..matched.len()].iter().enumerate().position(|(index, existing)| {
+                !matched[index]
+                    && existing.date == event.date
+                    && (existing.title == event.title
+                        || existing.event_type == event.event_type
+                            && matches!(existing.icon, EventIcon::Player { .. })
+                            && existing.icon == event.icon)
+            });
+
+            match duplicate {
+                Some(index) => matched[index] = true,
+                None => events.push(event),
+            }
+        }
+
+        events.sort_by_key(|e| e.date);
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum EventType {
     Blue,
@@ -58,7 +87,7 @@ pub enum EventType {
     Red,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum EventIcon {
     Player { uuid: Uuid },
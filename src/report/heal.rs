@@ -10,7 +10,7 @@ use crate::report::player::{Player, SimplePlayer};
 use crate::report::raw::Heal as RawHeal;
 use crate::report::report::since;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Heal {
     pub date: DateTime<FixedOffset>,
     pub since_beginning: Duration,
@@ -55,7 +55,7 @@ impl Heal {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum HealCause {
     Natural,
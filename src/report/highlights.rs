@@ -0,0 +1,282 @@
+//! Detects the highlights of a game from its lethal damages: first blood, kill streaks and
+//! multi-kills. They are shown in the players aggregates, and can be added to the history as
+//! events.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset};
+use uuid::Uuid;
+
+use crate::report::damage::Damage;
+use crate::report::event::{Event, EventIcon, EventType};
+use crate::report::item::names::Locale;
+use crate::report::player::SimplePlayer;
+use crate::report::settings::SettingsDamages;
+
+/// Several players killed by the same player in a short time (see
+/// `SettingsDamages::multi_kill_window`).
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct MultiKill {
+    /// The date of the last kill.
+    pub date: DateTime<FixedOffset>,
+    pub since_beginning: Duration,
+    pub victims: Vec<SimplePlayer>,
+}
+
+/// A kill streak: kills made by a player without dying.
+#[derive(Debug, Clone)]
+pub struct KillStreak {
+    pub kills: u32,
+
+    /// The date of the last kill of the streak.
+    pub date: DateTime<FixedOffset>,
+    pub since_beginning: Duration,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PlayerHighlights {
+    pub player: Option<SimplePlayer>,
+
+    /// The victim and date of the first kill of the game, if made by this player.
+    pub first_blood: Option<(SimplePlayer, DateTime<FixedOffset>, Duration)>,
+
+    pub longest_kill_streak: Option<KillStreak>,
+    pub multi_kills: Vec<MultiKill>,
+}
+
+/// Computes the highlights of every player who killed someone. Kills are credited like in the
+/// aggregates (including indirect kills), but players killing themselves are ignored.
+///
+/// The damages are expected to be sorted chronologically, and not grouped, so that kills are
+/// dated by their lethal hit.
+pub fn players_highlights(
+    damages: &[Damage],
+    settings: &SettingsDamages,
+) -> HashMap<Uuid, PlayerHighlights> {
+    let window = chrono::Duration::seconds(settings.multi_kill_window as i64);
+
+    let mut highlights: HashMap<Uuid, PlayerHighlights> = HashMap::new();
+    let mut streaks: HashMap<Uuid, u32> = HashMap::new();
+    let mut multi_kills: HashMap<Uuid, Vec<&Damage>> = HashMap::new();
+    let mut first_blood = true;
+
+    for damage in damages.iter().filter(|damage| damage.lethal) {
+        let victim = &damage.damagee;
+
+        // Dying ends kill streaks and multi-kills.
+        streaks.remove(&victim.uuid);
+        if let Some(kills) = multi_kills.remove(&victim.uuid) {
+            flush_multi_kill(highlights.entry(victim.uuid).or_default(), kills);
+        }
+
+        let killer = match damage.credited_player() {
+            Some(cause) if &cause.player != victim => &cause.player,
+            _ => continue,
+        };
+
+        let player_highlights = highlights.entry(killer.uuid).or_default();
+        player_highlights.player = Some(killer.clone());
+
+        if first_blood {
+            player_highlights.first_blood =
+                Some((victim.clone(), damage.date, damage.since_beginning));
+            first_blood = false;
+        }
+
+        let streak = streaks.entry(killer.uuid).or_insert(0);
+        *streak += 1;
+
+        match &player_highlights.longest_kill_streak {
+            Some(longest) if longest.kills >= *streak => {}
+            _ => {
+                player_highlights.longest_kill_streak = Some(KillStreak {
+                    kills: *streak,
+                    date: damage.date,
+                    since_beginning: damage.since_beginning,
+                })
+            }
+        }
+
+        let kills = multi_kills.entry(killer.uuid).or_default();
+        match kills.last() {
+            Some(last) if settings.multi_kill_window > 0 && damage.date - last.date <= window => {
+                kills.push(damage)
+            }
+            _ => {
+                let previous_kills = std::mem::replace(kills, vec![damage]);
+                flush_multi_kill(player_highlights, previous_kills);
+            }
+        }
+    }
+
+    for (uuid, kills) in multi_kills {
+        flush_multi_kill(highlights.entry(uuid).or_default(), kills);
+    }
+
+    highlights
+        .into_iter()
+        .filter(|(_, highlights)| highlights.player.is_some())
+        .collect()
+}
+
+fn flush_multi_kill(highlights: &mut PlayerHighlights, kills: Vec<&Damage>) {
+    if kills.len() < 2 {
+        return;
+    }
+
+    if let Some(last) = kills.last() {
+        highlights.multi_kills.push(MultiKill {
+            date: last.date,
+            since_beginning: last.since_beginning,
+            victims: kills.iter().map(|kill| kill.damagee.clone()).collect(),
+        });
+    }
+}
+
+/// Converts the highlights into golden events, to be displayed in the history. Only kill streaks
+/// of at least three kills are included. Titles and descriptions are written in the given
+/// language.
+pub fn highlights_events(
+    highlights: &HashMap<Uuid, PlayerHighlights>,
+    locale: Locale,
+) -> Vec<Event> {
+    let mut events: Vec<Event> = Vec::new();
+
+    for (uuid, highlights) in highlights {
+        let player = match &highlights.player {
+            Some(player) => player,
+            None => continue,
+        };
+
+        let event = |date: DateTime<FixedOffset>,
+                     since_beginning: Duration,
+                     (title, description): (String, String)| Event {
+            date,
+            since_beginning,
+            event_type: EventType::Gold,
+            title,
+            description: Some(description),
+            icon: EventIcon::Player { uuid: *uuid },
+        };
+
+        if let Some((victim, date, since_beginning)) = &highlights.first_blood {
+            events.push(event(
+                *date,
+                *since_beginning,
+                first_blood_texts(locale, &player.name, &victim.name),
+            ));
+        }
+
+        for multi_kill in &highlights.multi_kills {
+            events.push(event(
+                multi_kill.date,
+                multi_kill.since_beginning,
+                multi_kill_texts(locale, &player.name, &multi_kill.victims),
+            ));
+        }
+
+        if let Some(streak) = highlights
+            .longest_kill_streak
+            .as_ref()
+            .filter(|streak| streak.kills >= 3)
+        {
+            events.push(event(
+                streak.date,
+                streak.since_beginning,
+                kill_streak_texts(locale, &player.name, streak.kills),
+            ));
+        }
+    }
+
+    events.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.title.cmp(&b.title)));
+
+    events
+}
+
+fn first_blood_texts(locale: Locale, player: &str, victim: &str) -> (String, String) {
+    match locale {
+        Locale::En => (
+            format!("First blood for {}", player),
+            format!("{} is the first player killed.", victim),
+        ),
+        Locale::Fr => (
+            format!("Premier sang pour {}", player),
+            format!("{} est le premier joueur tué.", victim),
+        ),
+        Locale::It => (
+            format!("Primo sangue per {}", player),
+            format!("{} è il primo giocatore ucciso.", victim),
+        ),
+    }
+}
+
+fn multi_kill_texts(locale: Locale, player: &str, victims: &[SimplePlayer]) -> (String, String) {
+    let name = multi_kill_name(locale, victims.len());
+    let victims = names(locale, victims);
+
+    match locale {
+        Locale::En => (
+            format!("{} by {}", name, player),
+            format!("Killed {}.", victims),
+        ),
+        Locale::Fr => (
+            format!("{} pour {}", name, player),
+            format!("A tué {}.", victims),
+        ),
+        Locale::It => (
+            format!("{} per {}", name, player),
+            format!("Ha ucciso {}.", victims),
+        ),
+    }
+}
+
+fn kill_streak_texts(locale: Locale, player: &str, kills: u32) -> (String, String) {
+    match locale {
+        Locale::En => (
+            format!("{} kills in a row by {}", kills, player),
+            format!("{} killed {} players without dying.", player, kills),
+        ),
+        Locale::Fr => (
+            format!("{} meurtres d’affilée pour {}", kills, player),
+            format!("{} a tué {} joueurs sans mourir.", player, kills),
+        ),
+        Locale::It => (
+            format!("{} uccisioni di fila per {}", kills, player),
+            format!("{} ha ucciso {} giocatori senza morire.", player, kills),
+        ),
+    }
+}
+
+fn multi_kill_name(locale: Locale, kills: usize) -> &'static str {
+    match (locale, kills) {
+        (Locale::En, 2) => "Double kill",
+        (Locale::En, 3) => "Triple kill",
+        (Locale::En, 4) => "Quadruple kill",
+        (Locale::En, _) => "Multi-kill",
+        (Locale::Fr, 2) => "Double meurtre",
+        (Locale::Fr, 3) => "Triple meurtre",
+        (Locale::Fr, 4) => "Quadruple meurtre",
+        (Locale::Fr, _) => "Meurtre multiple",
+        (Locale::It, 2) => "Doppia uccisione",
+        (Locale::It, 3) => "Tripla uccisione",
+        (Locale::It, 4) => "Quadrupla uccisione",
+        (Locale::It, _) => "Uccisione multipla",
+    }
+}
+
+/// Joins players names into an enumeration (e.g. “A, B and C”).
+pub(crate) fn names(locale: Locale, players: &[SimplePlayer]) -> String {
+    let names: Vec<&str> = players.iter().map(|player| player.name.as_str()).collect();
+    let and = match locale {
+        Locale::En => "and",
+        Locale::Fr => "et",
+        Locale::It => "e",
+    };
+
+    match names.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, others)) => format!("{} {} {}", others.join(", "), and, last),
+        None => String::new(),
+    }
+}
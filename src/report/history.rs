@@ -0,0 +1,258 @@
+//! Generates history events from the processed damages, for plugins not emitting them: deaths,
+//! teams eliminations, and the winners announcement.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset};
+use uuid::Uuid;
+
+use crate::report::damage::{Damage, DamageCause};
+use crate::report::event::{Event, EventIcon, EventType};
+use crate::report::highlights::names;
+use crate::report::item::names::Locale;
+use crate::report::player::SimplePlayer;
+use crate::report::report::since;
+use crate::report::team::Team;
+
+/// Generates an event for each death, each team elimination and for the winners, sorted
+/// chronologically. Titles and descriptions are written in the given language; entity names are
+/// taken from their identifiers (e.g. “cave spider”), in every language.
+///
+/// `damages` must not be grouped, so that deaths are dated by their lethal hit. `eliminations` are
+/// the elimination dates of the players (see `aggregates::eliminations`), and `end` the date of the
+/// end of the game, when the winners are announced.
+pub fn generated_events(
+    damages: &[Damage],
+    teams: &[Team],
+    winners: &[SimplePlayer],
+    eliminations: &HashMap<Uuid, DateTime<FixedOffset>>,
+    begin: &DateTime<FixedOffset>,
+    end: &DateTime<FixedOffset>,
+    locale: Locale,
+) -> Vec<Event> {
+    let mut events: Vec<Event> = damages
+        .iter()
+        .filter(|damage| damage.lethal)
+        .map(|damage| death_event(damage, locale))
+        .collect();
+
+    events.extend(
+        teams
+            .iter()
+            .filter_map(|team| team_elimination_event(team, eliminations, begin, locale)),
+    );
+
+    if let Some(event) = winners_event(teams, winners, begin, end, locale) {
+        events.push(event);
+    }
+
+    events.sort_by_key(|e| e.date);
+
+    events
+}
+
+fn death_event(damage: &Damage, locale: Locale) -> Event {
+    let victim = &damage.damagee.name;
+
+    let killed_by = |killer: &str| match locale {
+        Locale::En => format!("{} was killed by {}", victim, killer),
+        Locale::Fr => format!("{} a été tué par {}", victim, killer),
+        Locale::It => format!("{} è stato ucciso da {}", victim, killer),
+    };
+
+    let title = match (damage.credited_player(), &damage.cause) {
+        (Some(killer), DamageCause::Player(_)) if killer.player != damage.damagee => {
+            killed_by(&killer.player.name)
+        }
+        (Some(killer), cause) if killer.player != damage.damagee => format!(
+            "{} ({})",
+            killed_by(&killer.player.name),
+            cause_name(cause, locale)
+        ),
+        (_, DamageCause::Entity(cause)) => killed_by(&entity_name(&cause.entity)),
+        (_, cause) => match locale {
+            Locale::En => format!("{} died ({})", victim, cause_name(cause, locale)),
+            Locale::Fr => format!("{} est mort ({})", victim, cause_name(cause, locale)),
+            Locale::It => format!("{} è morto ({})", victim, cause_name(cause, locale)),
+        },
+    };
+
+    Event {
+        date: damage.date,
+        since_beginning: damage.since_beginning,
+        event_type: EventType::Red,
+        title,
+        description: None,
+        icon: EventIcon::Player {
+            uuid: damage.damagee.uuid,
+        },
+    }
+}
+
+fn team_elimination_event(
+    team: &Team,
+    eliminations: &HashMap<Uuid, DateTime<FixedOffset>>,
+    begin: &DateTime<FixedOffset>,
+    locale: Locale,
+) -> Option<Event> {
+    // The team is eliminated with its last member, if all of them are.
+    let (last, date) = team
+        .players
+        .iter()
+        .map(|player| eliminations.get(&player.uuid).map(|date| (player, date)))
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .max_by_key(|(_, date)| **date)?;
+
+    let (title, description) = match locale {
+        Locale::En => (
+            format!("Team {} was eliminated", team.name),
+            format!("{} was the last member standing.", last.name),
+        ),
+        Locale::Fr => (
+            format!("L’équipe {} a été éliminée", team.name),
+            format!("{} était le dernier membre en vie.", last.name),
+        ),
+        Locale::It => (
+            format!("La squadra {} è stata eliminata", team.name),
+            format!("{} era l’ultimo membro in vita.", last.name),
+        ),
+    };
+
+    Some(Event {
+        date: *date,
+        since_beginning: since(date, begin),
+        event_type: EventType::Red,
+        title,
+        description: Some(description),
+        icon: EventIcon::Player { uuid: last.uuid },
+    })
+}
+
+fn winners_event(
+    teams: &[Team],
+    winners: &[SimplePlayer],
+    begin: &DateTime<FixedOffset>,
+    end: &DateTime<FixedOffset>,
+    locale: Locale,
+) -> Option<Event> {
+    let first = winners.first()?;
+
+    // If every winner belongs to the same team, the team is announced (even if some of its
+    // members were eliminated).
+    let winning_team = match &first.team {
+        Some(name) if winners.iter().all(|winner| winner.team.as_ref() == Some(name)) => {
+            teams.iter().find(|team| &team.name == name)
+        }
+        _ => None,
+    };
+
+    let title = match (winning_team, locale) {
+        (Some(team), Locale::En) => format!("Team {} won the game", team.name),
+        (Some(team), Locale::Fr) => format!("L’équipe {} a gagné la partie", team.name),
+        (Some(team), Locale::It) => format!("La squadra {} ha vinto la partita", team.name),
+        (None, Locale::En) => format!("{} won the game", names(locale, winners)),
+        (None, Locale::Fr) => format!(
+            "{} {} la partie",
+            names(locale, winners),
+            if winners.len() > 1 { "ont gagné" } else { "a gagné" }
+        ),
+        (None, Locale::It) => format!(
+            "{} {} la partita",
+            names(locale, winners),
+            if winners.len() > 1 { "hanno vinto" } else { "ha vinto" }
+        ),
+    };
+
+    Some(Event {
+        date: *end,
+        since_beginning: since(end, begin),
+        event_type: EventType::Green,
+        title,
+        description: None,
+        icon: EventIcon::Player { uuid: first.uuid },
+    })
+}
+
+/// A readable name for a damage cause (e.g. “block explosion” for `BlockExplosion`). Causes
+/// without translation are named in English.
+fn cause_name(cause: &DamageCause, locale: Locale) -> String {
+    match locale {
+        Locale::En => None,
+        Locale::Fr => french_cause_name(cause),
+        Locale::It => italian_cause_name(cause),
+    }
+    .map(str::to_string)
+    .unwrap_or_else(|| cause.to_string().replace('_', " "))
+}
+
+fn french_cause_name(cause: &DamageCause) -> Option<&'static str> {
+    Some(match cause {
+        DamageCause::BlockExplosion => "explosion",
+        DamageCause::Contact => "contact",
+        DamageCause::Cramming => "écrasement",
+        DamageCause::DragonBreath => "souffle du dragon",
+        DamageCause::Drowning => "noyade",
+        DamageCause::Dryout => "dessèchement",
+        DamageCause::Fall => "chute",
+        DamageCause::FallingBlock => "chute de bloc",
+        DamageCause::Fire | DamageCause::FireTick => "feu",
+        DamageCause::FlyIntoWall => "collision",
+        DamageCause::HotFloor => "bloc de magma",
+        DamageCause::Lava => "lave",
+        DamageCause::Lightning => "foudre",
+        DamageCause::Magic => "magie",
+        DamageCause::Melting => "fonte",
+        DamageCause::Poison => "poison",
+        DamageCause::Projectile => "projectile",
+        DamageCause::Starvation => "faim",
+        DamageCause::Suffocation => "suffocation",
+        DamageCause::Suicide => "suicide",
+        DamageCause::Thorns => "épines",
+        DamageCause::Void => "vide",
+        DamageCause::Wither => "wither",
+        DamageCause::Command => "commande",
+        DamageCause::Unknown => "inconnu",
+        _ => return None,
+    })
+}
+
+fn italian_cause_name(cause: &DamageCause) -> Option<&'static str> {
+    Some(match cause {
+        DamageCause::BlockExplosion => "esplosione",
+        DamageCause::Contact => "contatto",
+        DamageCause::Cramming => "schiacciamento",
+        DamageCause::DragonBreath => "soffio del drago",
+        DamageCause::Drowning => "annegamento",
+        DamageCause::Dryout => "disidratazione",
+        DamageCause::Fall => "caduta",
+        DamageCause::FallingBlock => "blocco caduto",
+        DamageCause::Fire | DamageCause::FireTick => "fuoco",
+        DamageCause::FlyIntoWall => "collisione",
+        DamageCause::HotFloor => "blocco di magma",
+        DamageCause::Lava => "lava",
+        DamageCause::Lightning => "fulmine",
+        DamageCause::Magic => "magia",
+        DamageCause::Melting => "scioglimento",
+        DamageCause::Poison => "veleno",
+        DamageCause::Projectile => "proiettile",
+        DamageCause::Starvation => "fame",
+        DamageCause::Suffocation => "soffocamento",
+        DamageCause::Suicide => "suicidio",
+        DamageCause::Thorns => "spine",
+        DamageCause::Void => "vuoto",
+        DamageCause::Wither => "wither",
+        DamageCause::Command => "comando",
+        DamageCause::Unknown => "sconosciuto",
+        _ => return None,
+    })
+}
+
+/// A readable name for an entity type (e.g. “cave spider” for `minecraft:cave_spider`).
+fn entity_name(entity: &str) -> String {
+    entity
+        .rsplit(':')
+        .next()
+        .unwrap_or(entity)
+        .replace('_', " ")
+}
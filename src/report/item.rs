@@ -1,5 +1,14 @@
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+
+pub mod components;
+pub mod names;
+pub mod potions;
+pub mod snbt;
+
 #[inline(always)]
-fn default_one_u8() -> u8 {
+pub(crate) fn default_one_u8() -> u8 {
     1
 }
 
@@ -8,8 +17,15 @@ fn default_one_u32() -> u32 {
     1
 }
 
-/// A Minecraft item.
+#[inline(always)]
+pub(crate) fn default_one_i32() -> i32 {
+    1
+}
+
+/// A Minecraft item. Items using data components (Minecraft 1.20.5+) are normalized into the
+/// legacy `tag` when deserialized (see `components::RawItem`).
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+#[serde(from = "components::RawItem")]
 pub struct Item {
     /// The Minecraft item identifier.
     pub id: String,
@@ -22,9 +38,19 @@ pub struct Item {
     pub tag: Option<ItemTag>
 }
 
+impl JsonSchema for Item {
+    fn schema_name() -> String {
+        "Item".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        components::RawItem::json_schema(gen)
+    }
+}
+
 /// Non-exhaustive item tags (the ones we need to know about) as of
 /// https://minecraft.gamepedia.com/Player.dat_format#Item_structure .
-#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Eq, PartialEq, Hash)]
 #[serde(rename_all = "PascalCase")]
 pub struct ItemTag {
     pub unbreakable: Option<u8>,  // Technically a boolean but stored as 0/1
@@ -37,7 +63,7 @@ pub struct ItemTag {
 }
 
 /// An enchantment applied to an item.
-#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Eq, PartialEq, Hash)]
 pub struct EnchantmentTag {
     pub id: String,
     #[serde(default = "default_one_u32")]
@@ -45,17 +71,18 @@ pub struct EnchantmentTag {
 }
 
 /// A potion effect (ignoring fields we don't care about, i.e. display-related ones).
-#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Eq, PartialEq, Hash)]
 #[serde(rename_all = "PascalCase")]
 pub struct PotionEffectTag {
     pub id: u8,
     pub amplifier: i8,
-    #[serde(default = "default_one_u32")]
-    pub duration: u32
+    /// In ticks; `-1` for infinite effects (since Minecraft 1.19.4).
+    #[serde(default = "default_one_i32")]
+    pub duration: i32
 }
 
 /// The display tag of an item
-#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Eq, PartialEq, Hash)]
 #[serde(rename_all = "PascalCase")]
 pub struct DisplayTag {
     pub name: Option<String>,
@@ -0,0 +1,208 @@
+//! Items data components, replacing the NBT `tag` since Minecraft 1.20.5. They are normalized into
+//! the legacy `ItemTag`, so items are processed and displayed the same way whatever the version
+//! of the game.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::report::item::potions::Effect;
+use crate::report::item::{
+    default_one_i32, default_one_u8, DisplayTag, EnchantmentTag, Item, ItemTag, PotionEffectTag,
+};
+
+/// An item as sent by plugins: with the legacy `tag` (and `Count`), or with `components` (and
+/// `count`), or both.
+#[derive(Deserialize, JsonSchema, Debug, Clone)]
+#[schemars(rename = "Item")]
+pub struct RawItem {
+    /// The Minecraft item identifier.
+    pub id: String,
+
+    /// How many of them?
+    #[serde(rename = "Count", alias = "count", default = "default_one_u8")]
+    pub count: u8,
+
+    /// Item metadata (if any), before Minecraft 1.20.5.
+    pub tag: Option<ItemTag>,
+
+    /// Item data components (if any), since Minecraft 1.20.5.
+    pub components: Option<ItemComponents>,
+}
+
+/// Non-exhaustive data components (the ones we need to know about) as of
+/// https://minecraft.wiki/w/Data_component_format .
+#[derive(Deserialize, JsonSchema, Debug, Clone, Default)]
+pub struct ItemComponents {
+    #[serde(rename = "minecraft:enchantments")]
+    pub enchantments: Option<EnchantmentsComponent>,
+
+    #[serde(rename = "minecraft:stored_enchantments")]
+    pub stored_enchantments: Option<EnchantmentsComponent>,
+
+    /// A text component, either as a JSON string or as an object.
+    #[serde(rename = "minecraft:custom_name")]
+    pub custom_name: Option<Value>,
+
+    #[serde(rename = "minecraft:lore")]
+    pub lore: Option<Vec<Value>>,
+
+    /// Present (usually as `{}`) if the item is unbreakable.
+    #[serde(rename = "minecraft:unbreakable")]
+    pub unbreakable: Option<Value>,
+
+    #[serde(rename = "minecraft:potion_contents")]
+    pub potion_contents: Option<PotionContentsComponent>,
+}
+
+/// Enchantments levels by enchantment ID, wrapped into `levels` before Minecraft 1.21.5.
+#[derive(Deserialize, JsonSchema, Debug, Clone)]
+#[serde(untagged)]
+pub enum EnchantmentsComponent {
+    Wrapped { levels: BTreeMap<String, u32> },
+    Levels(BTreeMap<String, u32>),
+}
+
+/// A potion type (e.g. `minecraft:strong_healing`), alone or with custom effects.
+#[derive(Deserialize, JsonSchema, Debug, Clone)]
+#[serde(untagged)]
+pub enum PotionContentsComponent {
+    Potion(String),
+    Contents {
+        potion: Option<String>,
+        custom_effects: Option<Vec<CustomEffectComponent>>,
+    },
+}
+
+#[derive(Deserialize, JsonSchema, Debug, Clone)]
+pub struct CustomEffectComponent {
+    /// The effect identifier, e.g. `minecraft:poison`.
+    pub id: String,
+
+    #[serde(default)]
+    pub amplifier: i8,
+
+    /// In ticks; `-1` for infinite effects.
+    #[serde(default = "default_one_i32")]
+    pub duration: i32,
+}
+
+impl From<RawItem> for Item {
+    fn from(raw_item: RawItem) -> Self {
+        let tag = match raw_item.components {
+            Some(components) => Some(components.normalize(raw_item.tag)),
+            None => raw_item.tag,
+        };
+
+        Item {
+            id: raw_item.id,
+            count: raw_item.count,
+            tag,
+        }
+    }
+}
+
+impl ItemComponents {
+    /// Converts the components into the legacy tag, filling (or replacing) the given one.
+    pub fn normalize(self, tag: Option<ItemTag>) -> ItemTag {
+        let mut tag = tag.unwrap_or(ItemTag {
+            unbreakable: None,
+            enchantments: None,
+            stored_enchantments: None,
+            custom_potion_effects: None,
+            potion: None,
+            display: None,
+        });
+
+        if let Some(enchantments) = self.enchantments {
+            tag.enchantments = Some(enchantments.into_tags());
+        }
+
+        if let Some(enchantments) = self.stored_enchantments {
+            tag.stored_enchantments = Some(enchantments.into_tags());
+        }
+
+        if self.unbreakable.is_some() {
+            tag.unbreakable = Some(1);
+        }
+
+        if self.custom_name.is_some() || self.lore.is_some() {
+            let display = tag.display.get_or_insert(DisplayTag {
+                name: None,
+                lore: None,
+            });
+
+            if let Some(name) = self.custom_name {
+                display.name = Some(text_component(name));
+            }
+
+            if let Some(lore) = self.lore {
+                display.lore = Some(lore.into_iter().map(text_component).collect());
+            }
+        }
+
+        match self.potion_contents {
+            Some(PotionContentsComponent::Potion(potion)) => tag.potion = Some(potion),
+            Some(PotionContentsComponent::Contents {
+                potion,
+                custom_effects,
+            }) => {
+                tag.potion = potion.or(tag.potion);
+
+                if let Some(effects) = custom_effects {
+                    tag.custom_potion_effects = Some(
+                        effects
+                            .into_iter()
+                            .filter_map(CustomEffectComponent::into_tag)
+                            .collect(),
+                    );
+                }
+            }
+            None => {}
+        }
+
+        tag
+    }
+}
+
+impl EnchantmentsComponent {
+    fn into_tags(self) -> Vec<EnchantmentTag> {
+        let levels = match self {
+            EnchantmentsComponent::Wrapped { levels } => levels,
+            EnchantmentsComponent::Levels(levels) => levels,
+        };
+
+        levels
+            .into_iter()
+            .map(|(id, lvl)| EnchantmentTag { id, lvl })
+            .collect()
+    }
+}
+
+impl CustomEffectComponent {
+    /// Converts the effect into the legacy numeric one. Unknown effects are ignored, as they
+    /// cannot be represented.
+    fn into_tag(self) -> Option<PotionEffectTag> {
+        let name = self.id.rsplit(':').next().unwrap_or(&self.id).to_string();
+        let effect: Effect = serde_json::from_value(Value::String(name)).ok()?;
+
+        Some(PotionEffectTag {
+            id: effect.id(),
+            amplifier: self.amplifier,
+            duration: self.duration,
+        })
+    }
+}
+
+/// Text components are stored as JSON strings in the legacy tag, like in Minecraft before 1.20.5.
+/// Before 1.21.5, components are themselves encoded as JSON strings: encoded plain strings (e.g.
+/// `"\"Excalibur\""`) are decoded, as the legacy tag stores them unquoted.
+fn text_component(component: Value) -> String {
+    match component {
+        Value::String(text) => match serde_json::from_str(&text) {
+            Ok(Value::String(decoded)) => decoded,
+            _ => text,
+        },
+        component => component.to_string(),
+    }
+}
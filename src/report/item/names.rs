@@ -0,0 +1,333 @@
+//! Human-readable and localized names of items and enchantments, in the languages of the web
+//! interface.
+
+use strum_macros::{AsRefStr, EnumString};
+
+use crate::report::item::{EnchantmentTag, Item};
+
+/// The languages names and generated texts can be translated to.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Locale {
+    En,
+    Fr,
+    It,
+}
+
+impl Default for Locale {
+    #[inline]
+    fn default() -> Self {
+        Locale::En
+    }
+}
+
+/// Enchantments having a single level, displayed without it.
+const SINGLE_LEVEL_ENCHANTMENTS: [&str; 9] = [
+    "aqua_affinity",
+    "binding_curse",
+    "channeling",
+    "flame",
+    "infinity",
+    "mending",
+    "multishot",
+    "silk_touch",
+    "vanishing_curse",
+];
+
+impl Item {
+    /// The name of this item as displayed in the game, followed by its enchantments or its potion
+    /// effects, e.g. “Diamond Sword (Sharpness III, Unbreaking II)”.
+    ///
+    /// If the item was renamed, its custom name is used, with its color codes.
+    pub fn display_name(&self, locale: Locale) -> String {
+        let display = self.tag.as_ref().and_then(|tag| tag.display.as_ref());
+        let name = match display.and_then(|display| display.name.as_ref()) {
+            Some(name) => custom_name(name),
+            None => item_name(&self.id, locale),
+        };
+
+        let details: Vec<String> = self
+            .tag
+            .as_ref()
+            .and_then(|tag| tag.enchantments.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|enchantment| enchantment.display_name(locale))
+            .chain(
+                self.potion_effects()
+                    .iter()
+                    .map(|effect| effect.display_name(locale)),
+            )
+            .collect();
+
+        match details.is_empty() {
+            true => name,
+            false => format!("{} ({})", name, details.join(", ")),
+        }
+    }
+}
+
+impl EnchantmentTag {
+    /// The name of this enchantment as displayed in the game, e.g. “Sharpness III”. The level is
+    /// omitted for single-level enchantments, like “Silk Touch”.
+    pub fn display_name(&self, locale: Locale) -> String {
+        let id = strip_namespace(&self.id);
+        let name = translate(locale, id).unwrap_or_else(|| title_case(id));
+
+        if self.lvl == 1 && SINGLE_LEVEL_ENCHANTMENTS.contains(&id) {
+            return name;
+        }
+
+        format!("{} {}", name, level_name(self.lvl))
+    }
+}
+
+/// A level in roman numerals, like in the game (e.g. “III”), or in digits if it cannot be
+/// converted.
+pub fn level_name(level: u32) -> String {
+    match roman::to(level as i32) {
+        Some(level) => level,
+        None => level.to_string(),
+    }
+}
+
+/// The name of an item from its identifier (e.g. “Diamond Sword” for `minecraft:diamond_sword`).
+/// Items without translation are named in English.
+pub fn item_name(id: &str, locale: Locale) -> String {
+    let id = strip_namespace(id);
+    translate(locale, id).unwrap_or_else(|| title_case(id))
+}
+
+/// Custom names are either raw strings (with color codes), or JSON text components since
+/// Minecraft 1.13, of which only the text is kept.
+fn custom_name(name: &str) -> String {
+    if !name.starts_with('{') {
+        return name.to_string();
+    }
+
+    match serde_json::from_str::<serde_json::Value>(name) {
+        Ok(component) => {
+            // Entries of `extra` may be plain strings instead of components.
+            let text = |component: &serde_json::Value| match component {
+                serde_json::Value::String(text) => text.clone(),
+                component => component["text"].as_str().unwrap_or_default().to_string(),
+            };
+
+            let mut name = text(&component);
+            if let Some(extra) = component["extra"].as_array() {
+                name.extend(extra.iter().map(text));
+            }
+
+            name
+        }
+        Err(_) => name.to_string(),
+    }
+}
+
+fn strip_namespace(id: &str) -> &str {
+    id.rsplit(':').next().unwrap_or(id).trim()
+}
+
+/// Converts an identifier into English title case (e.g. “Bane Of Arthropods” for
+/// `bane_of_arthropods`), for names without translation.
+pub(crate) fn title_case(id: &str) -> String {
+    id.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn translate(locale: Locale, id: &str) -> Option<String> {
+    match locale {
+        Locale::En => english(id),
+        Locale::Fr => french(id),
+        Locale::It => italian(id),
+    }
+    .map(str::to_string)
+    .or_else(|| tool_name(locale, id))
+}
+
+/// Names of tools and weapons made of some material (e.g. `iron_axe`).
+fn tool_name(locale: Locale, id: &str) -> Option<String> {
+    let (material, tool) = id.split_at(id.find('_')?);
+    let tool = &tool[1..];
+
+    let (tool, material) = match locale {
+        Locale::En => return None,
+        Locale::Fr => (
+            match tool {
+                "sword" => "Épée",
+                "axe" => "Hache",
+                "pickaxe" => "Pioche",
+                "shovel" => "Pelle",
+                "hoe" => "Houe",
+                _ => return None,
+            },
+            match material {
+                "wooden" => "en bois",
+                "stone" => "en pierre",
+                "iron" => "en fer",
+                "golden" => "en or",
+                "diamond" => "en diamant",
+                "netherite" => "en netherite",
+                _ => return None,
+            },
+        ),
+        Locale::It => (
+            match tool {
+                "sword" => "Spada",
+                "axe" => "Ascia",
+                "pickaxe" => "Piccone",
+                "shovel" => "Pala",
+                "hoe" => "Zappa",
+                _ => return None,
+            },
+            match material {
+                "wooden" => "di legno",
+                "stone" => "di pietra",
+                "iron" => "di ferro",
+                "golden" => "d'oro",
+                "diamond" => "di diamante",
+                "netherite" => "di netherite",
+                _ => return None,
+            },
+        ),
+    };
+
+    Some(format!("{} {}", tool, material))
+}
+
+/// English names differing from the title-cased identifier.
+fn english(id: &str) -> Option<&'static str> {
+    Some(match id {
+        "bane_of_arthropods" => "Bane of Arthropods",
+        "binding_curse" => "Curse of Binding",
+        "luck_of_the_sea" => "Luck of the Sea",
+        "sweeping" => "Sweeping Edge",
+        "vanishing_curse" => "Curse of Vanishing",
+        "tnt" => "TNT",
+        _ => return None,
+    })
+}
+
+fn french(id: &str) -> Option<&'static str> {
+    Some(match id {
+        // Enchantments
+        "aqua_affinity" => "Affinité aquatique",
+        "bane_of_arthropods" => "Fléau des arthropodes",
+        "binding_curse" => "Malédiction du lien éternel",
+        "blast_protection" => "Protection contre les explosions",
+        "channeling" => "Canalisation",
+        "depth_strider" => "Agilité aquatique",
+        "efficiency" => "Efficacité",
+        "feather_falling" => "Chute amortie",
+        "fire_aspect" => "Aura de feu",
+        "fire_protection" => "Protection contre le feu",
+        "flame" => "Flamme",
+        "fortune" => "Fortune",
+        "frost_walker" => "Semelles givrantes",
+        "impaling" => "Empalement",
+        "infinity" => "Infinité",
+        "knockback" => "Recul",
+        "looting" => "Butin",
+        "loyalty" => "Loyauté",
+        "luck_of_the_sea" => "Chance de la mer",
+        "lure" => "Appât",
+        "mending" => "Raccommodage",
+        "multishot" => "Tir multiple",
+        "piercing" => "Perforation",
+        "power" => "Puissance",
+        "projectile_protection" => "Protection contre les projectiles",
+        "protection" => "Protection",
+        "punch" => "Frappe",
+        "quick_charge" => "Charge rapide",
+        "respiration" => "Apnée",
+        "riptide" => "Impulsion",
+        "sharpness" => "Tranchant",
+        "silk_touch" => "Toucher de soie",
+        "smite" => "Châtiment",
+        "sweeping" => "Affilage",
+        "thorns" => "Épines",
+        "unbreaking" => "Solidité",
+        "vanishing_curse" => "Malédiction de disparition",
+
+        // Items
+        "bow" => "Arc",
+        "crossbow" => "Arbalète",
+        "trident" => "Trident",
+        "fishing_rod" => "Canne à pêche",
+        "stick" => "Bâton",
+        "arrow" => "Flèche",
+        "potion" => "Potion",
+        "splash_potion" => "Potion jetable",
+        "lingering_potion" => "Potion persistante",
+        "tipped_arrow" => "Flèche à effet",
+        "tnt" => "TNT",
+        _ => return None,
+    })
+}
+
+fn italian(id: &str) -> Option<&'static str> {
+    Some(match id {
+        // Enchantments
+        "aqua_affinity" => "Affinità acquatica",
+        "bane_of_arthropods" => "Flagello degli artropodi",
+        "binding_curse" => "Maledizione del legame",
+        "blast_protection" => "Protezione dalle esplosioni",
+        "channeling" => "Conduttività",
+        "depth_strider" => "Passo profondo",
+        "efficiency" => "Efficienza",
+        "feather_falling" => "Caduta piumata",
+        "fire_aspect" => "Aspetto di fuoco",
+        "fire_protection" => "Protezione dal fuoco",
+        "flame" => "Fiamma",
+        "fortune" => "Fortuna",
+        "frost_walker" => "Passo gelato",
+        "impaling" => "Impalamento",
+        "infinity" => "Infinità",
+        "knockback" => "Contraccolpo",
+        "looting" => "Saccheggio",
+        "loyalty" => "Fedeltà",
+        "luck_of_the_sea" => "Fortuna del mare",
+        "lure" => "Esca",
+        "mending" => "Riparazione",
+        "multishot" => "Tiro multiplo",
+        "piercing" => "Perforazione",
+        "power" => "Potenza",
+        "projectile_protection" => "Protezione dai proiettili",
+        "protection" => "Protezione",
+        "punch" => "Respinta",
+        "quick_charge" => "Carica rapida",
+        "respiration" => "Respirazione",
+        "riptide" => "Risucchio",
+        "sharpness" => "Affilatura",
+        "silk_touch" => "Tocco di velluto",
+        "smite" => "Anatema",
+        "sweeping" => "Filo tagliente",
+        "thorns" => "Spine",
+        "unbreaking" => "Indistruttibilità",
+        "vanishing_curse" => "Maledizione della scomparsa",
+
+        // Items
+        "bow" => "Arco",
+        "crossbow" => "Balestra",
+        "trident" => "Tridente",
+        "fishing_rod" => "Canna da pesca",
+        "stick" => "Bastone",
+        "arrow" => "Freccia",
+        "potion" => "Pozione",
+        "splash_potion" => "Pozione da lancio",
+        "lingering_potion" => "Pozione persistente",
+        "tipped_arrow" => "Freccia con effetto",
+        "tnt" => "TNT",
+        _ => return None,
+    })
+}
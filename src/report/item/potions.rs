@@ -0,0 +1,386 @@
+//! Decodes potions, from their custom effects or their vanilla potion type (e.g.
+//! `minecraft:strong_healing`), into effects with a level and a duration.
+
+use std::time::Duration;
+
+use strum_macros::{AsRefStr, Display};
+
+use crate::report::item::names::{level_name, title_case, Locale};
+use crate::report::item::{Item, ItemTag, PotionEffectTag};
+
+/// A status effect. Their numeric identifiers are given by their order in `EFFECTS`.
+#[derive(
+    Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, Hash, Display, AsRefStr,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Effect {
+    Speed,
+    Slowness,
+    Haste,
+    MiningFatigue,
+    Strength,
+    InstantHealth,
+    InstantDamage,
+    JumpBoost,
+    Nausea,
+    Regeneration,
+    Resistance,
+    FireResistance,
+    WaterBreathing,
+    Invisibility,
+    Blindness,
+    NightVision,
+    Hunger,
+    Weakness,
+    Poison,
+    Wither,
+    HealthBoost,
+    Absorption,
+    Saturation,
+    Glowing,
+    Levitation,
+    Luck,
+    Unluck,
+    SlowFalling,
+    ConduitPower,
+    DolphinsGrace,
+    BadOmen,
+    HeroOfTheVillage,
+    Darkness,
+    TrialOmen,
+    RaidOmen,
+    WindCharged,
+    Weaving,
+    Oozing,
+    Infested,
+}
+
+/// Every effect, ordered by numeric identifier (starting at 1), as in Minecraft.
+const EFFECTS: [Effect; 39] = [
+    Effect::Speed,
+    Effect::Slowness,
+    Effect::Haste,
+    Effect::MiningFatigue,
+    Effect::Strength,
+    Effect::InstantHealth,
+    Effect::InstantDamage,
+    Effect::JumpBoost,
+    Effect::Nausea,
+    Effect::Regeneration,
+    Effect::Resistance,
+    Effect::FireResistance,
+    Effect::WaterBreathing,
+    Effect::Invisibility,
+    Effect::Blindness,
+    Effect::NightVision,
+    Effect::Hunger,
+    Effect::Weakness,
+    Effect::Poison,
+    Effect::Wither,
+    Effect::HealthBoost,
+    Effect::Absorption,
+    Effect::Saturation,
+    Effect::Glowing,
+    Effect::Levitation,
+    Effect::Luck,
+    Effect::Unluck,
+    Effect::SlowFalling,
+    Effect::ConduitPower,
+    Effect::DolphinsGrace,
+    Effect::BadOmen,
+    Effect::HeroOfTheVillage,
+    Effect::Darkness,
+    Effect::TrialOmen,
+    Effect::RaidOmen,
+    Effect::WindCharged,
+    Effect::Weaving,
+    Effect::Oozing,
+    Effect::Infested,
+];
+
+impl Effect {
+    /// The effect with this numeric identifier, if it exists.
+    pub fn from_id(id: u8) -> Option<Self> {
+        EFFECTS.get((id as usize).checked_sub(1)?).copied()
+    }
+
+    /// The numeric identifier of this effect.
+    pub fn id(&self) -> u8 {
+        EFFECTS
+            .iter()
+            .position(|effect| effect == self)
+            .map_or(0, |index| index as u8 + 1)
+    }
+
+    /// Instant effects are applied once, and have no duration.
+    pub fn is_instant(&self) -> bool {
+        matches!(self, Effect::InstantHealth | Effect::InstantDamage)
+    }
+
+    pub fn display_name(&self, locale: Locale) -> String {
+        let name = match locale {
+            Locale::En => None,
+            Locale::Fr => french(*self),
+            Locale::It => italian(*self),
+        };
+
+        match name {
+            Some(name) => name.to_string(),
+            None => english(*self),
+        }
+    }
+}
+
+/// A decoded potion effect.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct PotionEffect {
+    pub effect: Effect,
+
+    /// The level of the effect, starting at 1.
+    pub level: u32,
+
+    /// `None` for instant and infinite effects.
+    pub duration: Option<Duration>,
+
+    /// `true` if the effect never ends.
+    pub infinite: bool,
+}
+
+impl PotionEffect {
+    /// A negative duration (in ticks) stands for an infinite effect.
+    fn new(effect: Effect, level: u32, ticks: i32) -> Self {
+        let infinite = ticks < 0 && !effect.is_instant();
+
+        PotionEffect {
+            effect,
+            level,
+            duration: match effect.is_instant() || infinite {
+                true => None,
+                false => Some(Duration::from_millis(ticks as u64 * 50)),
+            },
+            infinite,
+        }
+    }
+
+    /// The effect as displayed in the game, e.g. “Poison II (0:21)”, “Instant Health” or
+    /// “Speed (∞)”.
+    pub fn display_name(&self, locale: Locale) -> String {
+        let name = match self.level {
+            1 => self.effect.display_name(locale),
+            level => format!("{} {}", self.effect.display_name(locale), level_name(level)),
+        };
+
+        match self.duration {
+            Some(duration) => format!(
+                "{} ({}:{:02})",
+                name,
+                duration.as_secs() / 60,
+                duration.as_secs() % 60
+            ),
+            None if self.infinite => format!("{} (∞)", name),
+            None => name,
+        }
+    }
+}
+
+impl PotionEffectTag {
+    /// Decodes this effect, if its identifier is known.
+    pub fn decode(&self) -> Option<PotionEffect> {
+        Some(PotionEffect::new(
+            Effect::from_id(self.id)?,
+            (self.amplifier as i32 + 1).max(1) as u32,
+            self.duration,
+        ))
+    }
+}
+
+impl ItemTag {
+    /// The effects of the potion type, then the custom effects. Durations are the ones of drinkable
+    /// and splash potions (see `Item::potion_effects` for the others).
+    pub fn potion_effects(&self) -> Vec<PotionEffect> {
+        let mut effects = self
+            .potion
+            .as_deref()
+            .map(potion_type_effects)
+            .unwrap_or_default();
+
+        if let Some(custom_effects) = &self.custom_potion_effects {
+            effects.extend(custom_effects.iter().filter_map(PotionEffectTag::decode));
+        }
+
+        effects
+    }
+}
+
+impl Item {
+    /// The effects of this potion (or tipped arrow), with durations reduced for lingering potions
+    /// and tipped arrows like in the game. Empty for other items.
+    pub fn potion_effects(&self) -> Vec<PotionEffect> {
+        let divisor = match self.id.rsplit(':').next().unwrap_or(&self.id) {
+            "potion" | "splash_potion" => 1,
+            "lingering_potion" => 4,
+            "tipped_arrow" => 8,
+            _ => return vec![],
+        };
+
+        self.tag
+            .as_ref()
+            .map(ItemTag::potion_effects)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|mut effect| {
+                effect.duration = effect.duration.map(|duration| duration / divisor);
+                effect
+            })
+            .collect()
+    }
+}
+
+/// The effects of a vanilla potion type (e.g. `minecraft:long_poison`), with the durations of
+/// drinkable potions. Unknown types and potions without effect (e.g. `minecraft:awkward`) have
+/// none.
+pub fn potion_type_effects(potion: &str) -> Vec<PotionEffect> {
+    let potion = potion.rsplit(':').next().unwrap_or(potion);
+
+    let (variant, base) = match potion.split_once('_') {
+        Some((variant @ "long", base)) | Some((variant @ "strong", base)) => (variant, base),
+        _ => ("", potion),
+    };
+
+    const SECONDS: i32 = 20;
+    const MINUTES: i32 = 60 * SECONDS;
+
+    let effects: &[(Effect, u32, i32)] = match (base, variant) {
+        ("night_vision", "") => &[(Effect::NightVision, 1, 3 * MINUTES)],
+        ("night_vision", "long") => &[(Effect::NightVision, 1, 8 * MINUTES)],
+        ("invisibility", "") => &[(Effect::Invisibility, 1, 3 * MINUTES)],
+        ("invisibility", "long") => &[(Effect::Invisibility, 1, 8 * MINUTES)],
+        ("leaping", "") => &[(Effect::JumpBoost, 1, 3 * MINUTES)],
+        ("leaping", "long") => &[(Effect::JumpBoost, 1, 8 * MINUTES)],
+        ("leaping", "strong") => &[(Effect::JumpBoost, 2, 90 * SECONDS)],
+        ("fire_resistance", "") => &[(Effect::FireResistance, 1, 3 * MINUTES)],
+        ("fire_resistance", "long") => &[(Effect::FireResistance, 1, 8 * MINUTES)],
+        ("swiftness", "") => &[(Effect::Speed, 1, 3 * MINUTES)],
+        ("swiftness", "long") => &[(Effect::Speed, 1, 8 * MINUTES)],
+        ("swiftness", "strong") => &[(Effect::Speed, 2, 90 * SECONDS)],
+        ("slowness", "") => &[(Effect::Slowness, 1, 90 * SECONDS)],
+        ("slowness", "long") => &[(Effect::Slowness, 1, 4 * MINUTES)],
+        ("slowness", "strong") => &[(Effect::Slowness, 4, 20 * SECONDS)],
+        ("turtle_master", "") => &[
+            (Effect::Slowness, 4, 20 * SECONDS),
+            (Effect::Resistance, 3, 20 * SECONDS),
+        ],
+        ("turtle_master", "long") => &[
+            (Effect::Slowness, 4, 40 * SECONDS),
+            (Effect::Resistance, 3, 40 * SECONDS),
+        ],
+        ("turtle_master", "strong") => &[
+            (Effect::Slowness, 6, 20 * SECONDS),
+            (Effect::Resistance, 4, 20 * SECONDS),
+        ],
+        ("water_breathing", "") => &[(Effect::WaterBreathing, 1, 3 * MINUTES)],
+        ("water_breathing", "long") => &[(Effect::WaterBreathing, 1, 8 * MINUTES)],
+        ("healing", "") => &[(Effect::InstantHealth, 1, 1)],
+        ("healing", "strong") => &[(Effect::InstantHealth, 2, 1)],
+        ("harming", "") => &[(Effect::InstantDamage, 1, 1)],
+        ("harming", "strong") => &[(Effect::InstantDamage, 2, 1)],
+        ("poison", "") => &[(Effect::Poison, 1, 45 * SECONDS)],
+        ("poison", "long") => &[(Effect::Poison, 1, 90 * SECONDS)],
+        ("poison", "strong") => &[(Effect::Poison, 2, 432)],
+        ("regeneration", "") => &[(Effect::Regeneration, 1, 45 * SECONDS)],
+        ("regeneration", "long") => &[(Effect::Regeneration, 1, 90 * SECONDS)],
+        ("regeneration", "strong") => &[(Effect::Regeneration, 2, 450)],
+        ("strength", "") => &[(Effect::Strength, 1, 3 * MINUTES)],
+        ("strength", "long") => &[(Effect::Strength, 1, 8 * MINUTES)],
+        ("strength", "strong") => &[(Effect::Strength, 2, 90 * SECONDS)],
+        ("weakness", "") => &[(Effect::Weakness, 1, 90 * SECONDS)],
+        ("weakness", "long") => &[(Effect::Weakness, 1, 4 * MINUTES)],
+        ("luck", "") => &[(Effect::Luck, 1, 5 * MINUTES)],
+        ("slow_falling", "") => &[(Effect::SlowFalling, 1, 90 * SECONDS)],
+        ("slow_falling", "long") => &[(Effect::SlowFalling, 1, 4 * MINUTES)],
+        _ => &[],
+    };
+
+    effects
+        .iter()
+        .map(|(effect, level, ticks)| PotionEffect::new(*effect, *level, *ticks))
+        .collect()
+}
+
+fn english(effect: Effect) -> String {
+    match effect {
+        Effect::Unluck => "Bad Luck".to_string(),
+        Effect::DolphinsGrace => "Dolphin's Grace".to_string(),
+        Effect::HeroOfTheVillage => "Hero of the Village".to_string(),
+        effect => title_case(effect.as_ref()),
+    }
+}
+
+fn french(effect: Effect) -> Option<&'static str> {
+    Some(match effect {
+        Effect::Speed => "Rapidité",
+        Effect::Slowness => "Lenteur",
+        Effect::Haste => "Célérité",
+        Effect::MiningFatigue => "Fatigue",
+        Effect::Strength => "Force",
+        Effect::InstantHealth => "Soin instantané",
+        Effect::InstantDamage => "Dégâts instantanés",
+        Effect::JumpBoost => "Saut amélioré",
+        Effect::Nausea => "Nausée",
+        Effect::Regeneration => "Régénération",
+        Effect::Resistance => "Résistance",
+        Effect::FireResistance => "Résistance au feu",
+        Effect::WaterBreathing => "Apnée",
+        Effect::Invisibility => "Invisibilité",
+        Effect::Blindness => "Cécité",
+        Effect::NightVision => "Vision nocturne",
+        Effect::Hunger => "Faim",
+        Effect::Weakness => "Faiblesse",
+        Effect::Poison => "Poison",
+        Effect::Wither => "Wither",
+        Effect::HealthBoost => "Bonus de vie",
+        Effect::Absorption => "Absorption",
+        Effect::Saturation => "Saturation",
+        Effect::Glowing => "Surbrillance",
+        Effect::Levitation => "Lévitation",
+        Effect::Luck => "Chance",
+        Effect::Unluck => "Malchance",
+        Effect::SlowFalling => "Chute lente",
+        _ => return None,
+    })
+}
+
+fn italian(effect: Effect) -> Option<&'static str> {
+    Some(match effect {
+        Effect::Speed => "Velocità",
+        Effect::Slowness => "Lentezza",
+        Effect::Haste => "Rapidità",
+        Effect::MiningFatigue => "Affaticamento",
+        Effect::Strength => "Forza",
+        Effect::InstantHealth => "Salute istantanea",
+        Effect::InstantDamage => "Danno istantaneo",
+        Effect::JumpBoost => "Salto",
+        Effect::Nausea => "Nausea",
+        Effect::Regeneration => "Rigenerazione",
+        Effect::Resistance => "Resistenza",
+        Effect::FireResistance => "Resistenza al fuoco",
+        Effect::WaterBreathing => "Respirazione",
+        Effect::Invisibility => "Invisibilità",
+        Effect::Blindness => "Cecità",
+        Effect::NightVision => "Visione notturna",
+        Effect::Hunger => "Fame",
+        Effect::Weakness => "Debolezza",
+        Effect::Poison => "Veleno",
+        Effect::Wither => "Avvizzimento",
+        Effect::HealthBoost => "Salute aumentata",
+        Effect::Absorption => "Assorbimento",
+        Effect::Saturation => "Saturazione",
+        Effect::Glowing => "Luminescenza",
+        Effect::Levitation => "Levitazione",
+        Effect::Luck => "Fortuna",
+        Effect::Unluck => "Sfortuna",
+        Effect::SlowFalling => "Caduta lenta",
+        _ => return None,
+    })
+}
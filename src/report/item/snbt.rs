@@ -0,0 +1,287 @@
+//! Parses items written in SNBT (the “stringified” NBT format used by Minecraft commands, e.g.
+//! `{id:"minecraft:bow",Count:1b,tag:{Enchantments:[{id:"minecraft:power",lvl:5s}]}}`), so plugins
+//! can send them as dumped by the game instead of converting them to JSON.
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{Schema, SchemaObject, SubschemaValidation};
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Number, Value};
+
+use crate::report::errors::{InvalidReportError, ReportResult};
+use crate::report::item::Item;
+
+/// Values nested deeper than this are rejected, like serde_json does, so that the parser cannot
+/// overflow its stack.
+const MAX_DEPTH: usize = 128;
+
+/// Parses an SNBT item. If the SNBT is valid but is not an item, the error tells where in the
+/// item (e.g. `tag.Enchantments[0].lvl`).
+pub fn parse_item(snbt: &str) -> ReportResult<Item> {
+    serde_path_to_error::deserialize(parse(snbt)?).map_err(|error| {
+        InvalidReportError::InvalidSnbtItem {
+            path: error.path().to_string(),
+            reason: error.into_inner().to_string(),
+        }
+    })
+}
+
+/// Parses any SNBT value into JSON. Numbers lose their type suffix (`1b` becomes `1`), booleans
+/// become `1` or `0` like in the game, and typed arrays (`[I; 1, 2]`) become plain arrays.
+pub fn parse(snbt: &str) -> ReportResult<Value> {
+    let mut parser = Parser {
+        chars: snbt.chars().collect(),
+        position: 0,
+        depth: 0,
+    };
+
+    let value = parser.value()?;
+
+    parser.skip_whitespaces();
+    match parser.peek() {
+        Some(_) => Err(parser.error("unexpected trailing characters")),
+        None => Ok(value),
+    }
+}
+
+/// Deserializes an optional item, written either as a JSON object or as an SNBT string.
+pub fn deserialize_item<'de, D>(deserializer: D) -> Result<Option<Item>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::String(snbt)) => parse_item(&snbt)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+        Some(value) => serde_json::from_value(value)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// The JSON schema of items accepted by `deserialize_item`.
+pub fn item_schema(gen: &mut SchemaGenerator) -> Schema {
+    Schema::Object(SchemaObject {
+        subschemas: Some(Box::new(SubschemaValidation {
+            any_of: Some(vec![
+                gen.subschema_for::<Item>(),
+                gen.subschema_for::<String>(),
+                gen.subschema_for::<()>(),
+            ]),
+            ..Default::default()
+        })),
+        ..Default::default()
+    })
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+
+    /// The number of compounds and lists being parsed.
+    depth: usize,
+}
+
+impl Parser {
+    fn error(&self, reason: &str) -> InvalidReportError {
+        InvalidReportError::InvalidSnbt {
+            position: self.position,
+            reason: reason.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    /// Skips whitespaces, then consumes the expected character.
+    fn expect(&mut self, expected: char) -> ReportResult<()> {
+        self.skip_whitespaces();
+
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.position += 1;
+                Ok(())
+            }
+            _ => Err(self.error(&format!("expected `{}`", expected))),
+        }
+    }
+
+    fn value(&mut self) -> ReportResult<Value> {
+        self.skip_whitespaces();
+
+        match self.peek() {
+            Some(c @ '{') | Some(c @ '[') => {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.error("too deeply nested"));
+                }
+
+                self.depth += 1;
+                let value = match c {
+                    '{' => self.compound(),
+                    _ => self.list(),
+                };
+                self.depth -= 1;
+
+                value
+            }
+            Some('"') | Some('\'') => self.quoted_string().map(Value::String),
+            Some(_) => self.unquoted_value(),
+            None => Err(self.error("unexpected end")),
+        }
+    }
+
+    fn compound(&mut self) -> ReportResult<Value> {
+        self.expect('{')?;
+        let mut compound = Map::new();
+
+        self.skip_whitespaces();
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(Value::Object(compound));
+        }
+
+        loop {
+            self.skip_whitespaces();
+            let key = match self.peek() {
+                Some('"') | Some('\'') => self.quoted_string()?,
+                _ => self.unquoted_string(false)?,
+            };
+
+            self.expect(':')?;
+            compound.insert(key, self.value()?);
+
+            self.skip_whitespaces();
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some('}') => {
+                    self.position += 1;
+                    return Ok(Value::Object(compound));
+                }
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn list(&mut self) -> ReportResult<Value> {
+        self.expect('[')?;
+        let mut list = vec![];
+
+        // Typed arrays: [B; 1b, 2b], [I; 1, 2] or [L; 1L, 2L].
+        if let (Some('B'), Some(';')) | (Some('I'), Some(';')) | (Some('L'), Some(';')) =
+            (self.peek(), self.chars.get(self.position + 1).copied())
+        {
+            self.position += 2;
+        }
+
+        self.skip_whitespaces();
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(Value::Array(list));
+        }
+
+        loop {
+            list.push(self.value()?);
+
+            self.skip_whitespaces();
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(Value::Array(list));
+                }
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn quoted_string(&mut self) -> ReportResult<String> {
+        let quote = self.peek().ok_or_else(|| self.error("expected a string"))?;
+        self.position += 1;
+
+        let mut string = String::new();
+
+        loop {
+            match self.peek() {
+                Some('\\') => {
+                    self.position += 1;
+                    match self.peek() {
+                        Some(c) => string.push(c),
+                        None => return Err(self.error("unterminated string")),
+                    }
+                }
+                Some(c) if c == quote => {
+                    self.position += 1;
+                    return Ok(string);
+                }
+                Some(c) => string.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+
+            self.position += 1;
+        }
+    }
+
+    /// Unquoted strings may only contain letters, digits, `_`, `-`, `.` and `+`. In values, `:`
+    /// is also accepted, for unquoted namespaced identifiers (e.g. `minecraft:bow`).
+    fn unquoted_string(&mut self, in_value: bool) -> ReportResult<String> {
+        let start = self.position;
+
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || "_-.+".contains(c) || (in_value && c == ':') {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+
+        match self.position > start {
+            true => Ok(self.chars[start..self.position].iter().collect()),
+            false => Err(self.error("expected a value")),
+        }
+    }
+
+    fn unquoted_value(&mut self) -> ReportResult<Value> {
+        let token = self.unquoted_string(true)?;
+
+        Ok(match token.as_str() {
+            "true" => Value::from(1),
+            "false" => Value::from(0),
+            _ => number(&token).unwrap_or(Value::String(token)),
+        })
+    }
+}
+
+/// Parses an SNBT number, with its optional type suffix (`b`, `s`, `l`, `f` or `d`).
+fn number(token: &str) -> Option<Value> {
+    let (digits, suffix) = match token.chars().last()?.to_ascii_lowercase() {
+        suffix @ 'b' | suffix @ 's' | suffix @ 'l' | suffix @ 'f' | suffix @ 'd' => {
+            (&token[..token.len() - 1], Some(suffix))
+        }
+        _ => (token, None),
+    };
+
+    match suffix {
+        Some('b') | Some('s') | Some('l') => digits.parse::<i64>().ok().map(Value::from),
+        Some(_) => digits
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number),
+        None => match digits.parse::<i64>() {
+            Ok(integer) => Some(Value::from(integer)),
+            Err(_) if digits.contains(|c: char| c.is_ascii_digit()) => digits
+                .parse::<f64>()
+                .ok()
+                .and_then(Number::from_f64)
+                .map(Value::Number),
+            Err(_) => None,
+        },
+    }
+}
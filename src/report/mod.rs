@@ -1,14 +1,21 @@
 pub mod aggregates;
 pub mod damage;
+pub mod diff;
 pub mod errors;
 pub mod event;
 pub mod heal;
+pub mod highlights;
+pub mod history;
 pub mod item;
 pub mod player;
+pub mod rating;
 pub mod raw;
 pub mod report;
+pub mod schema;
+pub mod season;
 pub mod settings;
 pub mod team;
+pub mod validation;
 
 #[cfg(test)]
 mod test;
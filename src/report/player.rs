@@ -9,7 +9,7 @@ use crate::report::raw::Team as RawTeam;
 use crate::report::settings::SettingsPlayers;
 use crate::report::team::TeamColor;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Player {
     pub uuid: Uuid,
     pub name: String,
@@ -54,7 +54,7 @@ impl Player {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct PlayerStatistics {
     pub generic: Option<BTreeMap<String, u32>>,
     pub used: Option<BTreeMap<String, u32>>,
@@ -62,12 +62,41 @@ pub struct PlayerStatistics {
     pub picked_up: Option<BTreeMap<String, u32>>,
 }
 
+impl PlayerStatistics {
+    /// Sums these statistics with other ones of the same player (e.g. from another part of the
+    /// game, or another game of a season). Sums saturate at `u32::MAX`.
+    pub fn merge(self, other: PlayerStatistics) -> PlayerStatistics {
+        PlayerStatistics {
+            generic: Self::merge_group(self.generic, other.generic),
+            used: Self::merge_group(self.used, other.used),
+            mined: Self::merge_group(self.mined, other.mined),
+            picked_up: Self::merge_group(self.picked_up, other.picked_up),
+        }
+    }
+
+    fn merge_group(
+        first: Option<BTreeMap<String, u32>>,
+        second: Option<BTreeMap<String, u32>>,
+    ) -> Option<BTreeMap<String, u32>> {
+        match (first, second) {
+            (Some(mut first), Some(second)) => {
+                for (statistic, value) in second {
+                    let total = first.entry(statistic).or_insert(0);
+                    *total = total.saturating_add(value);
+                }
+                Some(first)
+            }
+            (first, second) => first.or(second),
+        }
+    }
+}
+
 ///
 /// Stores statistics as they will be displayed according
 /// to the settings, separating those visible by default and
 /// those hidden under a link because they are not highlighted.
 ///
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct DisplayedPlayerStatistics {
     pub generic: Option<DisplayedStatistics>,
     pub used: Option<DisplayedStatistics>,
@@ -79,13 +108,13 @@ pub struct DisplayedPlayerStatistics {
 /// For a single piece of statistics (global, mined, …), stores the visible
 /// ones and the by-default-hidden.
 ///
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct DisplayedStatistics {
     pub visible: Vec<Statistic>,
     pub hidden: Vec<Statistic>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, PartialOrd)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum Statistic {
     Duration {
@@ -323,7 +352,7 @@ impl DisplayedPlayerStatistics {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Eq, Hash)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Eq, Hash)]
 pub struct SimplePlayer {
     pub uuid: Uuid,
     pub name: String,
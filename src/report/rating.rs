@@ -0,0 +1,195 @@
+//! Rates players skill over a series of games, using the Weng-Lin Bayesian approximation (a
+//! TrueSkill-like model, with the Bradley-Terry full pairing): each player has an estimated skill
+//! `mu` and an uncertainty `sigma`, updated after each game from the placements of the teams.
+//!
+//! Ratings only depend on the games and their order, so they can always be recomputed from the
+//! stored reports.
+
+use std::collections::BTreeMap;
+
+use uuid::Uuid;
+
+use crate::report::report::Report;
+
+/// The skill of a new player.
+pub const INITIAL_MU: f64 = 25.0;
+
+/// The uncertainty of the skill of a new player.
+pub const INITIAL_SIGMA: f64 = INITIAL_MU / 3.0;
+
+/// The skill difference giving a ~76% chance of winning.
+const BETA: f64 = INITIAL_SIGMA / 2.0;
+
+/// The minimal factor the variance can be reduced by in a single game, so it stays positive.
+const KAPPA: f64 = 0.0001;
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct Rating {
+    /// The name of the player in their latest rated game.
+    pub name: String,
+
+    pub mu: f64,
+    pub sigma: f64,
+
+    /// A conservative estimate of the skill (`mu - 3 × sigma`), to sort players by.
+    pub rating: f64,
+
+    pub games: u32,
+}
+
+impl Rating {
+    fn new(name: String) -> Self {
+        Rating {
+            name,
+            mu: INITIAL_MU,
+            sigma: INITIAL_SIGMA,
+            rating: INITIAL_MU - 3.0 * INITIAL_SIGMA,
+            games: 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Default, PartialEq)]
+pub struct Ratings {
+    pub players: BTreeMap<Uuid, Rating>,
+}
+
+/// A team (or a player without team) in a rated game.
+struct RatedTeam {
+    players: Vec<Uuid>,
+    rank: u32,
+    mu: f64,
+    sigma_squared: f64,
+}
+
+impl Ratings {
+    /// Rates the players of all these games, played in chronological order (games at the same
+    /// date are ordered by match UUID).
+    pub fn from_reports(reports: &[Report]) -> Self {
+        let mut reports: Vec<&Report> = reports.iter().collect();
+        reports.sort_by_key(|report| (report.date, report.match_uuid));
+
+        let mut ratings = Ratings::default();
+
+        for report in reports {
+            ratings.rate(report);
+        }
+
+        ratings
+    }
+
+    /// Updates the ratings of the players of this game, according to the placements of their
+    /// teams (see `PlayerAlterationsAggregate::rank`). Players without team play on their own.
+    pub fn rate(&mut self, report: &Report) {
+        for player in &report.players {
+            let rating = self
+                .players
+                .entry(player.uuid)
+                .or_insert_with(|| Rating::new(player.name.clone()));
+
+            rating.name = player.name.clone();
+        }
+
+        // Team members missing from the players of the report are rated too.
+        for player in report.teams.iter().flat_map(|team| &team.players) {
+            self.players
+                .entry(player.uuid)
+                .or_insert_with(|| Rating::new(player.name.clone()));
+        }
+
+        let mut teams: Vec<Vec<Uuid>> = report
+            .teams
+            .iter()
+            .map(|team| team.players.iter().map(|player| player.uuid).collect())
+            .collect();
+
+        teams.extend(
+            report
+                .players
+                .iter()
+                .filter(|player| player.team.is_none())
+                .map(|player| vec![player.uuid]),
+        );
+
+        let mut teams: Vec<RatedTeam> = teams
+            .into_iter()
+            .filter(|players| !players.is_empty())
+            .map(|mut players| {
+                players.sort();
+
+                RatedTeam {
+                    rank: players
+                        .iter()
+                        .filter_map(|uuid| report.aggregates.players_damages.get(uuid))
+                        .map(|aggregate| aggregate.rank)
+                        .min()
+                        .unwrap_or(u32::MAX),
+                    mu: players
+                        .iter()
+                        .filter_map(|uuid| self.players.get(uuid))
+                        .map(|rating| rating.mu)
+                        .sum(),
+                    sigma_squared: players
+                        .iter()
+                        .filter_map(|uuid| self.players.get(uuid))
+                        .map(|rating| rating.sigma.powi(2))
+                        .sum(),
+                    players,
+                }
+            })
+            .collect();
+
+        // Sorted so floating-point sums are always computed in the same order.
+        teams.sort_by(|a, b| a.players.cmp(&b.players));
+
+        if teams.len() < 2 {
+            return;
+        }
+
+        let updates: Vec<(f64, f64)> = teams
+            .iter()
+            .map(|team| Self::team_update(team, &teams))
+            .collect();
+
+        for (team, (omega, delta)) in teams.iter().zip(updates) {
+            for uuid in &team.players {
+                if let Some(rating) = self.players.get_mut(uuid) {
+                    let sigma_squared = rating.sigma.powi(2);
+                    let weight = sigma_squared / team.sigma_squared;
+
+                    rating.mu += weight * omega;
+                    rating.sigma = (sigma_squared * (1.0 - weight * delta).max(KAPPA)).sqrt();
+                    rating.rating = rating.mu - 3.0 * rating.sigma;
+                    rating.games += 1;
+                }
+            }
+        }
+    }
+
+    /// Computes the skill (`omega`) and variance (`delta`) updates of a team against all the
+    /// others.
+    fn team_update(team: &RatedTeam, teams: &[RatedTeam]) -> (f64, f64) {
+        let mut omega = 0.0;
+        let mut delta = 0.0;
+
+        for other in teams.iter().filter(|other| other.players != team.players) {
+            let c = (team.sigma_squared + other.sigma_squared + 2.0 * BETA.powi(2)).sqrt();
+            let p = 1.0 / (1.0 + ((other.mu - team.mu) / c).exp());
+
+            let score = if team.rank < other.rank {
+                1.0
+            } else if team.rank == other.rank {
+                0.5
+            } else {
+                0.0
+            };
+
+            let gamma = team.sigma_squared.sqrt() / c;
+
+            omega += team.sigma_squared / c * (score - p);
+            delta += gamma * team.sigma_squared / c.powi(2) * p * (1.0 - p);
+        }
+
+        (omega, delta)
+    }
+}
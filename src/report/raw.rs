@@ -3,6 +3,9 @@ use uuid::Uuid;
 
 use crate::report::*;
 
+pub mod merge;
+pub mod migrations;
+
 #[inline(always)]
 fn default_false() -> bool {
     false
@@ -12,7 +15,7 @@ pub fn default_team_color() -> team::TeamColor {
     team::TeamColor::None
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Player {
     pub uuid: Uuid,
     pub name: String,
@@ -24,7 +27,7 @@ pub struct Player {
     pub statistics: Option<player::PlayerStatistics>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Team {
     pub name: String,
 
@@ -34,7 +37,7 @@ pub struct Team {
     pub players: Vec<Uuid>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Damage {
     pub date: DateTime<FixedOffset>,
     pub cause: DamageCause,
@@ -46,7 +49,7 @@ pub struct Damage {
     pub lethal: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE", tag = "type")]
 pub enum DamageCause {
     Player(PlayerDamageCause),
@@ -81,13 +84,17 @@ pub enum DamageCause {
     Unknown,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct PlayerDamageCause {
     pub player: Uuid,
+
+    /// The weapon, either as JSON or as an SNBT string.
+    #[serde(default, deserialize_with = "item::snbt::deserialize_item")]
+    #[schemars(schema_with = "item::snbt::item_schema")]
     pub weapon: Option<item::Item>
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Heal {
     pub date: DateTime<FixedOffset>,
     pub cause: heal::HealCause,
@@ -95,7 +102,7 @@ pub struct Heal {
     pub heal: u16,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Event {
     pub date: DateTime<FixedOffset>,
 
@@ -108,8 +115,11 @@ pub struct Event {
     pub icon: event::EventIcon,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Report {
+    #[serde(default = "migrations::current_format_version")]
+    pub format_version: u32,
+
     pub match_uuid: Uuid,
     pub title: String,
     pub date: DateTime<FixedOffset>,
@@ -0,0 +1,91 @@
+//! Merges partial raw reports of the same match into one, e.g. when a server restarted during a
+//! long game and the plugin wrote a report for each part of it.
+
+use crate::report::errors::{InvalidReportError, ReportResult};
+use crate::report::raw::{Player, Report, Team};
+
+/// Merges raw reports sharing the same `match_uuid` into a single report, which can then be
+/// processed like any other.
+///
+/// Reports are ordered by their beginning date (the order they are given in breaks ties); the
+/// merged report begins with the first one, and keeps its title and settings. Otherwise:
+///
+/// - players are merged by UUID, using the names and tag lines of the latest report, and summing
+///   their statistics;
+/// - teams are merged by name, with all their players;
+/// - winners are the ones of the latest report declaring some;
+/// - damages, heals and events are all kept, sorted chronologically.
+pub fn merge_reports(mut reports: Vec<Report>) -> ReportResult<Report> {
+    reports.sort_by_key(|report| report.date);
+
+    let mut reports = reports.into_iter();
+    let mut merged = reports.next().ok_or(InvalidReportError::NothingToMerge)?;
+
+    for report in reports {
+        if report.match_uuid != merged.match_uuid {
+            return Err(InvalidReportError::MismatchedMatchUuid {
+                expected: merged.match_uuid,
+                found: report.match_uuid,
+            });
+        }
+
+        if merged.minecraft.is_none() {
+            merged.minecraft = report.minecraft;
+        }
+
+        for player in report.players {
+            merge_player(&mut merged.players, player);
+        }
+
+        for team in report.teams {
+            merge_team(&mut merged.teams, team);
+        }
+
+        if let Some(winners) = report.winners.filter(|winners| !winners.is_empty()) {
+            merged.winners = Some(winners);
+        }
+
+        merged.damages.extend(report.damages);
+        merged.heals.extend(report.heals);
+        merged.events.extend(report.events);
+    }
+
+    merged.damages.sort_by_key(|damage| damage.date);
+    merged.heals.sort_by_key(|heal| heal.date);
+    merged.events.sort_by_key(|event| event.date);
+
+    Ok(merged)
+}
+
+fn merge_player(players: &mut Vec<Player>, player: Player) {
+    match players.iter_mut().find(|p| p.uuid == player.uuid) {
+        Some(existing) => {
+            existing.name = player.name;
+            existing.tag_line = player.tag_line.or_else(|| existing.tag_line.take());
+            existing.tag_line_secondary = player
+                .tag_line_secondary
+                .or_else(|| existing.tag_line_secondary.take());
+            existing.tag_line_details = player
+                .tag_line_details
+                .or_else(|| existing.tag_line_details.take());
+            existing.statistics = match (existing.statistics.take(), player.statistics) {
+                (Some(existing), Some(statistics)) => Some(existing.merge(statistics)),
+                (existing, statistics) => existing.or(statistics),
+            };
+        }
+        None => players.push(player),
+    }
+}
+
+fn merge_team(teams: &mut Vec<Team>, team: Team) {
+    match teams.iter_mut().find(|t| t.name == team.name) {
+        Some(existing) => {
+            for player in team.players {
+                if !existing.players.contains(&player) {
+                    existing.players.push(player);
+                }
+            }
+        }
+        None => teams.push(team),
+    }
+}
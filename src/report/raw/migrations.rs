@@ -0,0 +1,251 @@
+//! Upgrades raw reports written in an older format to the current one.
+//!
+//! Each migration works on the JSON value (as older reports cannot be deserialized into the
+//! current structures) and upgrades the report by exactly one version. Reports are upgraded
+//! step by step until they reach `CURRENT_FORMAT_VERSION`.
+//!
+//! The known formats are:
+//!
+//! 1. damages are flat, with the cause as a string (entities causes being the entity type), the
+//!    damager UUID in `damager` and the weapon as a Bukkit material in `weapon` (with its name and
+//!    enchantments in `weapon_name` and `weapon_enchantments`);
+//! 2. damage causes are objects tagged with a `cause` key, and weapons are Minecraft items, but
+//!    enchantments are stored as a map from enchantment to level; the settings use the
+//!    `damages_per_teams` key;
+//! 3. the current format, where damage causes are tagged with a `type` key and enchantments are
+//!    stored like Minecraft does.
+//!
+//! Reports without a `format_version` field are from before this field existed: their version
+//! is guessed from their shape.
+
+use serde_json::{json, Map, Value};
+
+use crate::report::errors::{InvalidReportError, ReportResult};
+use crate::report::raw::DamageCause;
+
+pub const CURRENT_FORMAT_VERSION: u32 = 3;
+
+#[inline(always)]
+pub fn current_format_version() -> u32 {
+    CURRENT_FORMAT_VERSION
+}
+
+/// Upgrades a raw JSON report to the current format, so it can be deserialized into a
+/// `raw::Report`. Reports already in the current format are returned untouched (except for the
+/// `format_version` field, which is always set).
+pub fn migrate(mut report: Value) -> ReportResult<Value> {
+    let mut version = detect_format_version(&report);
+
+    if version == 0 || version > CURRENT_FORMAT_VERSION as u64 {
+        return Err(InvalidReportError::UnsupportedFormatVersion { version });
+    }
+
+    while version < CURRENT_FORMAT_VERSION as u64 {
+        match version {
+            1 => migrate_v1_to_v2(&mut report),
+            2 => migrate_v2_to_v3(&mut report),
+            _ => unreachable!(),
+        }
+
+        version += 1;
+    }
+
+    if let Value::Object(report) = &mut report {
+        report.insert("format_version".to_string(), json!(CURRENT_FORMAT_VERSION));
+    }
+
+    Ok(report)
+}
+
+/// Returns the format version of a raw JSON report, either as declared, or guessed from its
+/// shape if it was generated before the `format_version` field existed.
+pub fn detect_format_version(report: &Value) -> u64 {
+    if let Some(version) = report.get("format_version") {
+        return version.as_u64().unwrap_or(0);
+    }
+
+    let causes = report
+        .get("damages")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|damage| damage.get("cause"));
+
+    for cause in causes {
+        if cause.is_string() {
+            return 1;
+        }
+        if cause.get("cause").is_some() && cause.get("type").is_none() {
+            return 2;
+        }
+    }
+
+    match report.pointer("/settings/damages/damages_per_teams") {
+        Some(_) => 2,
+        None => CURRENT_FORMAT_VERSION as u64,
+    }
+}
+
+fn migrate_v1_to_v2(report: &mut Value) {
+    if let Some(damages) = report.get_mut("damages").and_then(Value::as_array_mut) {
+        for damage in damages.iter_mut().filter_map(Value::as_object_mut) {
+            let weapon = v1_weapon_to_item(
+                damage.remove("weapon"),
+                damage.remove("weapon_name"),
+                damage.remove("weapon_enchantments"),
+            );
+
+            let cause = match damage.get("cause").and_then(Value::as_str) {
+                Some(cause) => cause.to_string(),
+                None => continue,
+            };
+
+            let cause = match cause.as_str() {
+                "PLAYER" => json!({
+                    "cause": "PLAYER",
+                    "player": damage.get("damager").cloned().unwrap_or(Value::Null),
+                    "weapon": weapon,
+                }),
+                _ if is_environmental_cause(&cause) => json!({ "cause": cause }),
+                entity => json!({
+                    "cause": "ENTITY",
+                    "entity": format!("minecraft:{}", entity.to_lowercase()),
+                    "weapon": weapon,
+                }),
+            };
+
+            damage.insert("cause".to_string(), cause);
+        }
+    }
+
+    if let Some(damages_settings) = report
+        .pointer_mut("/settings/damages")
+        .and_then(Value::as_object_mut)
+    {
+        if let Some(per_team) = damages_settings.remove("damages_per_team") {
+            damages_settings.insert("damages_per_teams".to_string(), per_team);
+        }
+    }
+}
+
+fn migrate_v2_to_v3(report: &mut Value) {
+    if let Some(damages) = report.get_mut("damages").and_then(Value::as_array_mut) {
+        for cause in damages
+            .iter_mut()
+            .filter_map(|damage| damage.get_mut("cause"))
+            .filter_map(Value::as_object_mut)
+        {
+            if let Some(cause_type) = cause.remove("cause") {
+                cause.insert("type".to_string(), cause_type);
+            }
+
+            if let Some(tag) = cause
+                .get_mut("weapon")
+                .and_then(|weapon| weapon.get_mut("tag"))
+                .and_then(Value::as_object_mut)
+            {
+                for key in &["Enchantments", "StoredEnchantments"] {
+                    if let Some(Value::Object(enchantments)) = tag.remove(*key) {
+                        tag.insert(key.to_string(), v2_enchantments_to_list(enchantments));
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(damages_settings) = report
+        .pointer_mut("/settings/damages")
+        .and_then(Value::as_object_mut)
+    {
+        if let Some(per_team) = damages_settings.remove("damages_per_teams") {
+            damages_settings.insert("damages_per_team".to_string(), per_team);
+        }
+    }
+}
+
+/// Checks if this (v1) damage cause is one of the non-creature causes we know about. Other causes
+/// are entity types.
+fn is_environmental_cause(cause: &str) -> bool {
+    match serde_json::from_value::<DamageCause>(json!({ "type": cause })) {
+        Ok(cause) => !matches!(cause, DamageCause::Player(_) | DamageCause::Entity(_)),
+        Err(_) => false,
+    }
+}
+
+/// Converts a v1 weapon (a Bukkit material with optional name and enchantments) into a Minecraft
+/// item, as stored in v2 reports. Fists and unknown weapons are no weapon at all.
+fn v1_weapon_to_item(
+    material: Option<Value>,
+    name: Option<Value>,
+    enchantments: Option<Value>,
+) -> Value {
+    let material = match material.as_ref().and_then(Value::as_str) {
+        Some("FISTS") | Some("UNKNOWN") | None => return Value::Null,
+        Some(material) => material,
+    };
+
+    let mut tag = Map::new();
+
+    if let Some(Value::String(name)) = name {
+        tag.insert("display".to_string(), json!({ "Name": name }));
+    }
+
+    if let Some(Value::Object(enchantments)) = enchantments {
+        tag.insert("Enchantments".to_string(), Value::Object(enchantments));
+    }
+
+    let mut item = Map::new();
+    item.insert("id".to_string(), json!(legacy_material_to_id(material)));
+
+    if !tag.is_empty() {
+        item.insert("tag".to_string(), Value::Object(tag));
+    }
+
+    Value::Object(item)
+}
+
+/// Converts a pre-1.13 Bukkit material name (e.g. `SWORD_DIAMOND` or `BOW`) into a Minecraft
+/// item identifier (e.g. `minecraft:diamond_sword` or `minecraft:bow`).
+fn legacy_material_to_id(material: &str) -> String {
+    let material = material.to_lowercase();
+    let mut parts = material.splitn(2, '_');
+
+    match (parts.next(), parts.next()) {
+        (Some(tool @ "sword"), Some(tier))
+        | (Some(tool @ "axe"), Some(tier))
+        | (Some(tool @ "pickaxe"), Some(tier))
+        | (Some(tool @ "spade"), Some(tier))
+        | (Some(tool @ "hoe"), Some(tier)) => format!(
+            "minecraft:{}_{}",
+            match tier {
+                "wood" => "wooden",
+                "gold" => "golden",
+                tier => tier,
+            },
+            match tool {
+                "spade" => "shovel",
+                tool => tool,
+            }
+        ),
+        _ => format!("minecraft:{}", material),
+    }
+}
+
+/// Converts v2 enchantments (a map from enchantment name to level) into a list of enchantments
+/// tags, as stored by Minecraft.
+fn v2_enchantments_to_list(enchantments: Map<String, Value>) -> Value {
+    Value::Array(
+        enchantments
+            .into_iter()
+            .map(|(id, lvl)| {
+                json!({
+                    "id": match id.contains(':') {
+                        true => id,
+                        false => format!("minecraft:{}", id),
+                    },
+                    "lvl": lvl,
+                })
+            })
+            .collect(),
+    )
+}
@@ -9,7 +9,7 @@ use uuid::Uuid;
 use crate::report::*;
 use crate::report::errors::ReportResult;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Report {
     pub match_uuid: Uuid,
     pub title: String,
@@ -24,9 +24,26 @@ pub struct Report {
     pub events: Vec<event::Event>,
     pub aggregates: aggregates::Aggregate,
     pub has_players_without_team: bool,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<validation::ValidationProblem>,
 }
 
 impl Report {
+    ///
+    /// Processes a raw report given by an user, repairing
+    /// broken references instead of failing. Every problem
+    /// found in the raw report is kept in `warnings`.
+    ///
+    pub fn from_raw_lenient(raw_report: raw::Report) -> ReportResult<Self> {
+        let warnings = validation::validate_report(&raw_report);
+        let mut report = Self::from_raw(validation::repair_report(raw_report))?;
+
+        report.warnings = warnings;
+
+        Ok(report)
+    }
+
     ///
     /// Processes a raw report given by an user
     /// and returns a ready-to-be-serialized-and-used
@@ -69,7 +86,9 @@ impl Report {
         // Damage::from_raw_vec expect damages to be sorted by chronological order.
         damages.sort_by_key(|d| d.date);
 
-        let damages = damage::Damage::from_raw_vec(damages, &players, &begin)?;
+        // Each hit is kept to replay the health of the players; damages are displayed grouped.
+        let hits = damage::Damage::from_raw_vec(damages, &players, &begin, &settings.damages)?;
+        let damages = damage::Damage::group(hits.clone());
         let heals = heal::Heal::from_raw_vec(raw_report.heals, &players, &begin)?;
 
         let winners = match raw_report.winners {
@@ -84,10 +103,15 @@ impl Report {
 
                 team_players
             },
-            _ => Self::extract_winners(&players, &damages),
+            _ => Self::extract_winners(&players, &hits, settings.game_mode),
         };
 
-        let aggregates = aggregates::Aggregate::from_raw(&players, &damages, &heals, &begin, &settings.players);
+        let teams = team::Team::from_raw_vec(teams, &players)?;
+
+        let highlights = highlights::players_highlights(&hits, &settings.damages);
+
+        let aggregates = aggregates::Aggregate::from_raw(&players, &teams, &damages, &hits, &heals, &begin, &settings)
+            .with_highlights(&highlights);
 
         let mut players_list: Vec<player::Player> = players
             .iter()
@@ -96,6 +120,35 @@ impl Report {
 
         players_list.sort_by(|a, b| a.name.cmp(&b.name));
 
+        let mut events = event::Event::from_raw_vec(raw_report.events, &begin);
+
+        let mut generated: Vec<event::Event> = Vec::new();
+
+        if settings.summary.generated_events {
+            let end = hits.iter().map(|d| d.date)
+                .chain(heals.iter().map(|h| h.date))
+                .chain(events.iter().map(|e| e.date))
+                .max()
+                .unwrap_or(begin);
+
+            generated.extend(history::generated_events(
+                &hits,
+                &teams,
+                if settings.winners { &winners } else { &[] },
+                &aggregates::eliminations(&hits, settings.game_mode),
+                &begin,
+                &end,
+                settings.summary.locale,
+            ));
+        }
+
+        if settings.summary.highlights {
+            generated.extend(highlights::highlights_events(&highlights, settings.summary.locale));
+        }
+
+        // Generated events are only compared to the plugin's events, not to each other.
+        event::Event::merge_generated(&mut events, generated);
+
         Ok(Report {
             match_uuid: raw_report.match_uuid,
             title: raw_report.title,
@@ -103,8 +156,8 @@ impl Report {
             minecraft: raw_report.minecraft,
             settings,
             players: players_list,
-            teams: team::Team::from_raw_vec(teams, &players)?,
-            events: event::Event::from_raw_vec(raw_report.events, &begin),
+            teams,
+            events,
             aggregates,
             winners,
             damages,
@@ -112,23 +165,21 @@ impl Report {
             has_players_without_team: players
                 .iter()
                 .any(|(_uuid, player)| player.team.is_none()),
+            warnings: vec![],
         })
     }
 
     fn extract_winners(
         players: &HashMap<Uuid, Rc<player::Player>>,
-        damages: &Vec<damage::Damage>,
+        hits: &[damage::Damage],
+        game_mode: settings::GameMode,
     ) -> Vec<player::SimplePlayer> {
-        let deads: Vec<Uuid> = damages
-            .iter()
-            .filter(|damage| damage.lethal)
-            .map(|damage| damage.damagee.uuid)
-            .collect();
+        let eliminated = aggregates::eliminations(hits, game_mode);
 
         let mut winners: Vec<player::SimplePlayer> = players
             .iter()
             .map(|(_uuid, player)| player)
-            .filter(|player| !deads.contains(&player.as_ref().uuid))
+            .filter(|player| !eliminated.contains_key(&player.as_ref().uuid))
             .map(|player| player.as_ref().into())
             .collect();
 
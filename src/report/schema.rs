@@ -0,0 +1,14 @@
+use schemars::schema::RootSchema;
+
+use crate::report::*;
+
+/// Generates the JSON Schema of the raw reports, as sent by the plugins.
+pub fn raw_report_schema() -> RootSchema {
+    schema_for!(raw::Report)
+}
+
+/// Generates the JSON Schema of the processed reports, as stored and displayed by the web
+/// interface.
+pub fn processed_report_schema() -> RootSchema {
+    schema_for!(report::Report)
+}
@@ -0,0 +1,137 @@
+//! Aggregates the processed reports of several games (e.g. a whole season) into a leaderboard.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset};
+use uuid::Uuid;
+
+use crate::report::player::PlayerStatistics;
+use crate::report::report::Report;
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct Season {
+    pub games: u32,
+    pub first_game: Option<DateTime<FixedOffset>>,
+    pub last_game: Option<DateTime<FixedOffset>>,
+
+    /// The players of the season, best first (see `Season::from_reports`).
+    pub players: Vec<SeasonPlayer>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct SeasonPlayer {
+    pub uuid: Uuid,
+
+    /// The name of the player in their latest game.
+    pub name: String,
+
+    /// The other names used by the player during the season, oldest first.
+    pub former_names: Vec<String>,
+
+    pub games_played: u32,
+    pub wins: u32,
+    pub kills: u32,
+    pub deaths: u32,
+    pub average_rank: f32,
+    pub damages_caused_total: u32,
+    pub damages_taken_total: u32,
+
+    /// The sum of the player statistics of every game.
+    pub statistics: Option<PlayerStatistics>,
+}
+
+impl Season {
+    /// Aggregates processed reports into season statistics. Players are identified by UUID, so
+    /// they are tracked even if they changed their name between two games.
+    ///
+    /// Players are sorted by wins, then kills, then average rank, then name.
+    pub fn from_reports(reports: &[Report]) -> Self {
+        let mut reports: Vec<&Report> = reports.iter().collect();
+        reports.sort_by_key(|report| report.date);
+
+        let mut players: HashMap<Uuid, SeasonPlayer> = HashMap::new();
+        let mut ranks_totals: HashMap<Uuid, u32> = HashMap::new();
+
+        for report in &reports {
+            for player in &report.players {
+                let season_player = players.entry(player.uuid).or_insert_with(|| SeasonPlayer {
+                    uuid: player.uuid,
+                    name: player.name.clone(),
+                    former_names: vec![],
+                    games_played: 0,
+                    wins: 0,
+                    kills: 0,
+                    deaths: 0,
+                    average_rank: 0.0,
+                    damages_caused_total: 0,
+                    damages_taken_total: 0,
+                    statistics: None,
+                });
+
+                if season_player.name != player.name {
+                    let former_name =
+                        std::mem::replace(&mut season_player.name, player.name.clone());
+                    season_player
+                        .former_names
+                        .retain(|name| name != &player.name);
+                    if !season_player.former_names.contains(&former_name) {
+                        season_player.former_names.push(former_name);
+                    }
+                }
+
+                season_player.games_played += 1;
+
+                if report
+                    .winners
+                    .iter()
+                    .any(|winner| winner.uuid == player.uuid)
+                {
+                    season_player.wins += 1;
+                }
+
+                if let Some(aggregate) = report.aggregates.players_damages.get(&player.uuid) {
+                    season_player.kills += aggregate.kills.len() as u32;
+                    season_player.deaths += aggregate.deaths();
+                    season_player.damages_caused_total += aggregate.damages_caused_total;
+                    season_player.damages_taken_total += aggregate.damages_taken_total;
+                    *ranks_totals.entry(player.uuid).or_insert(0) += aggregate.rank;
+                }
+
+                season_player.statistics =
+                    match (season_player.statistics.take(), &player.statistics) {
+                        (Some(total), Some(statistics)) => Some(total.merge(statistics.clone())),
+                        (total, statistics) => total.or_else(|| statistics.clone()),
+                    };
+            }
+        }
+
+        let mut players: Vec<SeasonPlayer> = players
+            .into_iter()
+            .map(|(uuid, mut player)| {
+                player.average_rank = ranks_totals.get(&uuid).copied().unwrap_or(0) as f32
+                    / player.games_played as f32;
+                player
+            })
+            .collect();
+
+        players.sort_by(|a, b| {
+            b.wins
+                .cmp(&a.wins)
+                .then_with(|| b.kills.cmp(&a.kills))
+                .then_with(|| {
+                    a.average_rank
+                        .partial_cmp(&b.average_rank)
+                        .unwrap_or(Ordering::Equal)
+                })
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        Season {
+            games: reports.len() as u32,
+            first_game: reports.first().map(|report| report.date),
+            last_game: reports.last().map(|report| report.date),
+            players,
+        }
+    }
+}
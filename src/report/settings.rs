@@ -1,4 +1,6 @@
-#[derive(Serialize, Deserialize, Debug, Clone)]
+use crate::report::item::names::Locale;
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Settings {
     #[serde(default = "default_true")]
     pub date: bool,
@@ -9,6 +11,9 @@ pub struct Settings {
     #[serde(default = "default_true")]
     pub winners: bool,
 
+    #[serde(default)]
+    pub game_mode: GameMode,
+
     #[serde(default = "default_summary_settings")]
     pub summary: SettingsSummary,
 
@@ -21,7 +26,41 @@ pub struct Settings {
     pub generator: Option<SettingsGenerator>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// How players are eliminated from the game, depending on the number of lives they have.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", tag = "type")]
+pub enum GameMode {
+    /// Players are eliminated on their first death (e.g. UHC).
+    Elimination,
+
+    /// Players are eliminated on their `lives`-th death (e.g. Run). `0` is treated as `1`.
+    Lives { lives: u32 },
+
+    /// Players respawn indefinitely and are never eliminated (e.g. Arena or KOTH). As the
+    /// placements depend on eliminations, all players share the first rank.
+    Respawn,
+}
+
+impl GameMode {
+    /// The number of deaths eliminating a player (at least one), or `None` if players are never
+    /// eliminated.
+    pub fn lives(&self) -> Option<u32> {
+        match self {
+            GameMode::Elimination => Some(1),
+            GameMode::Lives { lives } => Some((*lives).max(1)),
+            GameMode::Respawn => None,
+        }
+    }
+}
+
+impl Default for GameMode {
+    #[inline]
+    fn default() -> Self {
+        GameMode::Elimination
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct SettingsSummary {
     #[serde(default = "default_true")]
     pub enabled: bool,
@@ -34,9 +73,22 @@ pub struct SettingsSummary {
 
     #[serde(default = "default_true")]
     pub teams: bool,
+
+    /// Adds first blood, multi-kills and kill streaks to the history.
+    #[serde(default = "default_false")]
+    pub highlights: bool,
+
+    /// Adds deaths, teams eliminations and winners to the history, for plugins not emitting
+    /// these events.
+    #[serde(default = "default_false")]
+    pub generated_events: bool,
+
+    /// The language of the generated events and highlights: `en` (default), `fr` or `it`.
+    #[serde(default)]
+    pub locale: Locale,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct SettingsDamages {
     #[serde(default = "default_true")]
     pub enabled: bool,
@@ -45,16 +97,39 @@ pub struct SettingsDamages {
     pub damages_per_players: bool,
 
     #[serde(default = "default_true")]
-    pub damages_per_team: bool, // TODO unimplemented
+    pub damages_per_team: bool,
 
     #[serde(default = "default_true")]
     pub damages_from_environment: bool,
 
     #[serde(default = "default_true")]
     pub display_killer: bool,
+
+    /// If a player dies from the environment (e.g. a fall or lava) at most this many seconds
+    /// after being hit by another player, the kill is credited to this player. `0` disables it.
+    #[serde(default = "default_kill_attribution_window")]
+    pub kill_attribution_window: u32,
+
+    /// Players who damaged a victim at most this many seconds before their death are credited
+    /// with an assist. `0` disables assists.
+    #[serde(default = "default_assist_window")]
+    pub assist_window: u32,
+
+    /// Kills made by a player at most this many seconds after their previous kill are grouped into
+    /// a multi-kill. `0` disables multi-kills.
+    #[serde(default = "default_multi_kill_window")]
+    pub multi_kill_window: u32,
+
+    /// The health players start with, in half-hearts, used to compute health timelines.
+    #[serde(default = "default_max_health")]
+    pub max_health: u16,
+
+    /// The absorption players start with, in half-hearts, lost before health when damaged.
+    #[serde(default)]
+    pub absorption: u16,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct SettingsPlayers {
     #[serde(default = "default_true")]
     pub enabled: bool,
@@ -99,7 +174,7 @@ pub struct SettingsPlayers {
     pub picked_up_highlight: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct SettingsGenerator {
     pub name: String,
     pub link: Option<String>,
@@ -120,6 +195,7 @@ pub fn default_settings() -> Settings {
         date: true,
         players_count: true,
         winners: true,
+        game_mode: GameMode::default(),
         summary: default_summary_settings(),
         damages: default_damages_settings(),
         players: default_players_settings(),
@@ -134,6 +210,9 @@ fn default_summary_settings() -> SettingsSummary {
         history: true,
         players: true,
         teams: true,
+        highlights: false,
+        generated_events: false,
+        locale: Locale::default(),
     }
 }
 
@@ -145,9 +224,34 @@ fn default_damages_settings() -> SettingsDamages {
         damages_per_team: true,
         damages_from_environment: true,
         display_killer: true,
+        kill_attribution_window: default_kill_attribution_window(),
+        assist_window: default_assist_window(),
+        multi_kill_window: default_multi_kill_window(),
+        max_health: default_max_health(),
+        absorption: 0,
     }
 }
 
+#[inline(always)]
+fn default_kill_attribution_window() -> u32 {
+    5
+}
+
+#[inline(always)]
+fn default_assist_window() -> u32 {
+    10
+}
+
+#[inline(always)]
+fn default_multi_kill_window() -> u32 {
+    10
+}
+
+#[inline(always)]
+fn default_max_health() -> u16 {
+    20
+}
+
 #[inline(always)]
 fn default_players_settings() -> SettingsPlayers {
     SettingsPlayers {
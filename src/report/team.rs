@@ -7,7 +7,7 @@ use crate::report::errors::{InvalidReportError, ReportResult};
 use crate::report::player::{Player, SimplePlayer};
 use crate::report::raw::Team as RawTeam;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Team {
     pub name: String,
     pub color: TeamColor,
@@ -38,7 +38,7 @@ impl Team {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Copy, Clone, Eq, PartialEq, Hash)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TeamColor {
     Aqua,
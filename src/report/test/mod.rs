@@ -2,29 +2,82 @@ extern crate insta;
 
 use std::fs;
 use std::path::Path;
+use std::time::Duration;
 
 use insta::*;
 
 use crate::*;
+use crate::html::render_report;
+use crate::report::aggregates::{HealthPoint, WeaponAggregate};
+use crate::report::damage::DamageCause;
+use crate::report::item::Item;
+use crate::report::item::names::Locale;
+use crate::report::item::potions::Effect;
+use crate::report::item::snbt;
+use crate::report::raw;
 use crate::report::errors::ReportResult;
 use crate::report::report::Report;
+use crate::report::settings::GameMode;
+use crate::report::schema::{processed_report_schema, raw_report_schema};
+use crate::report::validation::{validate_report, ValidationProblem};
 
-fn process_from_path(path: &Path) -> ReportResult<Report> {
+fn read_input(path: &Path) -> String {
     match fs::read_to_string(path) {
-        Ok(input) => match serde_json::from_str(input.as_str()) {
-            Ok(raw_report) => report::report::Report::from_raw(raw_report),
-            Err(e) => panic!("Unable to parse raw report: {}", e)
-        },
+        Ok(input) => input,
         Err(e) => panic!("Unable to read input report test file: {}", e)
     }
 }
 
+fn input_path(input: &'static str) -> String {
+    format!("./src/report/test/inputs/{}.json", input)
+}
+
+fn migrate_from_path(path: &Path) -> serde_json::Value {
+    match serde_json::from_str(read_input(path).as_str()) {
+        Ok(raw_report) => match raw::migrations::migrate(raw_report) {
+            Ok(migrated) => migrated,
+            Err(e) => panic!("Unable to migrate raw report: {}", e)
+        },
+        Err(e) => panic!("Unable to parse raw report: {}", e)
+    }
+}
+
+fn read_raw_from_path(path: &Path) -> raw::Report {
+    match serde_json::from_value(migrate_from_path(path)) {
+        Ok(raw_report) => raw_report,
+        Err(e) => panic!("Unable to parse raw report: {}", e)
+    }
+}
+
+fn read_raw(input: &'static str) -> raw::Report {
+    read_raw_from_path(Path::new(input_path(input).as_str()))
+}
+
+fn process_from_path(path: &Path) -> ReportResult<Report> {
+    Report::from_raw(read_raw_from_path(path))
+}
+
+fn migrate_to_string(input: &'static str) -> String {
+    match serde_json::to_string_pretty(&migrate_from_path(Path::new(input_path(input).as_str()))) {
+        Ok(json_report) => json_report,
+        Err(e) => panic!("Unable to convert migrated report to json: {}", e)
+    }
+}
+
+fn validate(input: &'static str) -> Vec<ValidationProblem> {
+    validate_report(&read_raw(input))
+}
+
 fn process(input: &'static str) -> ReportResult<Report> {
-    process_from_path(Path::new(format!("./src/report/test/inputs/{}.json", input).as_str()))
+    process_from_path(Path::new(input_path(input).as_str()))
 }
 
 fn process_to_string(input: &'static str) -> String {
-    match process(input) {
+    report_to_string(process(input))
+}
+
+fn report_to_string(report: ReportResult<Report>) -> String {
+    match report {
         Ok(report) => match serde_json::to_string_pretty(&report) {
             Ok(json_report) => json_report,
             Err(e) => panic!("Unable to convert processed report to json: {}", e)
@@ -33,6 +86,24 @@ fn process_to_string(input: &'static str) -> String {
     }
 }
 
+fn assert_valid_against_schema(schema: schemars::schema::RootSchema, instance: &serde_json::Value) {
+    let schema = serde_json::to_value(schema).expect("Unable to convert schema to json");
+    let compiled = jsonschema::JSONSchema::compile(&schema).expect("Invalid JSON Schema");
+
+    let errors: Vec<String> = match compiled.validate(instance) {
+        Ok(()) => vec![],
+        Err(errors) => errors
+            .map(|error| format!("{} (at {})", error, error.instance_path))
+            .collect(),
+    };
+
+    assert!(
+        errors.is_empty(),
+        "Instance does not validate against the schema: {}",
+        errors.join(", ")
+    );
+}
+
 fn assert_input_with_snapshot(input: &'static str) {
     assert_snapshot!(input, process_to_string(input));
 }
@@ -95,3 +166,895 @@ fn test_grouped_damages_environment() {
 fn test_grouped_damages_with_multiple_actors() {
     assert_input_with_snapshot("grouped_damages_multiple_actors_environment");
 }
+
+#[test]
+fn test_validation_of_valid_report() {
+    assert_eq!(validate("empty"), vec![]);
+    assert_eq!(validate("grouped_damages_multiple_actors_environment"), vec![]);
+}
+
+#[test]
+fn test_validation_reports_every_problem() {
+    assert_eq!(validate("broken_player_link").len(), 1);
+    assert_snapshot!(
+        "validation_invalid_report",
+        serde_json::to_string_pretty(&validate("invalid_report")).unwrap()
+    );
+}
+
+#[test]
+fn test_lenient_processing_repairs_broken_references() {
+    assert_snapshot!(
+        "lenient_broken_player_link",
+        report_to_string(Report::from_raw_lenient(read_raw("broken_player_link")))
+    );
+
+    let report = Report::from_raw_lenient(read_raw("invalid_report")).unwrap();
+    assert_eq!(report.players.len(), 5);
+    assert_eq!(report.teams[1].players.len(), 1);
+    assert_eq!(report.warnings, validate("invalid_report"));
+}
+
+#[test]
+fn test_migrations() {
+    assert_snapshot!("migration_v1", migrate_to_string("migration_v1"));
+    assert_snapshot!("migration_v2", migrate_to_string("migration_v2"));
+    assert_snapshot!("migration_current", migrate_to_string("generator"));
+}
+
+#[test]
+fn test_migrated_examples_can_be_processed() {
+    assert!(process_from_path(Path::new("./examples/report-example.json")).is_ok());
+    assert!(process_from_path(Path::new("./examples/report-example-full.json")).is_ok());
+}
+
+#[test]
+fn test_unsupported_format_version_should_fail() {
+    assert!(raw::migrations::migrate(serde_json::json!({ "format_version": 42 })).is_err());
+}
+
+#[test]
+fn test_report_schemas() {
+    assert_snapshot!(
+        "raw_report_schema",
+        serde_json::to_string_pretty(&raw_report_schema()).unwrap()
+    );
+    assert_snapshot!(
+        "processed_report_schema",
+        serde_json::to_string_pretty(&processed_report_schema()).unwrap()
+    );
+}
+
+#[test]
+fn test_example_validates_against_schemas() {
+    let example = Path::new("./examples/report-example-full.json");
+
+    // The example is validated as shipped, without migration.
+    assert_valid_against_schema(
+        raw_report_schema(),
+        &serde_json::from_str(read_input(example).as_str()).unwrap(),
+    );
+    assert_valid_against_schema(
+        processed_report_schema(),
+        &serde_json::to_value(process_from_path(example).unwrap()).unwrap(),
+    );
+}
+
+#[test]
+fn test_html_rendering() {
+    assert_snapshot!(
+        "html_grouped_damages_multiple_actors_environment",
+        render_report(&process("grouped_damages_multiple_actors_environment").unwrap())
+    );
+    assert_snapshot!(
+        "html_report_example_full",
+        render_report(&process_from_path(Path::new("./examples/report-example-full.json")).unwrap())
+    );
+    assert_snapshot!("html_team_damages", render_report(&process("team_damages").unwrap()));
+
+    // Weapons of entities are shown, with their potion effects.
+    assert!(render_report(&process("entity_with_potion").unwrap())
+        .contains("Witch <span class=\"muted\">with Splash Potion (Instant Damage)</span>"));
+}
+
+#[test]
+fn test_team_damages() {
+    let report = process("team_damages").unwrap();
+    let teams = &report.aggregates.teams_damages;
+
+    let red = &teams["Rouges"];
+    assert_eq!(red.damages_caused_total, 20);
+    assert_eq!(red.damages_taken_total, 22);
+    assert_eq!(red.friendly_fire_total, 2);
+    assert_eq!(red.heals_total, 4);
+    assert_eq!((red.kills, red.deaths), (1, 1));
+    assert!(red.eliminated_at.is_none());
+
+    let blue = &teams["Bleus"];
+    assert_eq!(blue.damages_caused_total, 20);
+    assert_eq!(blue.damages_taken_total, 40);
+    assert_eq!(blue.friendly_fire_total, 0);
+    assert_eq!((blue.kills, blue.deaths), (1, 2));
+    assert_eq!(blue.eliminated_at, Some(Duration::from_secs(19 * 60 + 27)));
+}
+
+#[test]
+fn test_team_damages_can_be_disabled() {
+    let mut raw_report = read_raw("team_damages");
+    raw_report.settings.damages.damages_per_team = false;
+
+    assert!(Report::from_raw(raw_report).unwrap().aggregates.teams_damages.is_empty());
+}
+
+#[test]
+fn test_friendly_fire() {
+    let report = process("friendly_fire").unwrap();
+
+    let flags: Vec<bool> = report.damages.iter().map(|damage| damage.friendly_fire).collect();
+    assert_eq!(flags, vec![false, false, true]);
+
+    let player = |uuid: &str| &report.aggregates.players_damages[&uuid.parse().unwrap()];
+
+    let betrayed = player("55b89885-673c-4458-a8bd-33ad9c6190b4");
+    assert_eq!(betrayed.friendly_fire_taken_total, 12);
+    assert_eq!(betrayed.friendly_fire_caused_total, 0);
+
+    let traitor = player("0dd34bda-c13b-473b-a887-368027ca05ca");
+    assert_eq!(traitor.friendly_fire_caused_total, 12);
+    assert_eq!(traitor.team_kills, 1);
+
+    let team = &report.aggregates.teams_damages["Rouges"];
+    assert_eq!(team.friendly_fire_total, 12);
+    assert_eq!(team.team_kills, 1);
+    assert_eq!(team.kills, 0);
+}
+
+#[test]
+fn test_indirect_kills_are_credited_to_last_attacker() {
+    let report = process("indirect_kills").unwrap();
+    let player = |uuid: &str| &report.aggregates.players_damages[&uuid.parse().unwrap()];
+
+    let killer = player("da04cd54-c6c7-4672-97c5-85663f5bccf6");
+    assert_eq!(killer.kills.len(), 1);
+    assert_eq!(killer.kills[0].name, "Dada_exe");
+
+    let doomed = player("55b89885-673c-4458-a8bd-33ad9c6190b4");
+    assert!(matches!(&doomed.killed_by, Some(DamageCause::Player(cause)) if cause.player.name == "AmauryPi"));
+    assert_eq!(doomed.death_cause, Some(DamageCause::Fall));
+
+    // Hit ten seconds before falling into the void: outside of the attribution window.
+    let fallen = player("0dd34bda-c13b-473b-a887-368027ca05ca");
+    assert_eq!(fallen.killed_by, Some(DamageCause::Void));
+    assert_eq!(fallen.death_cause, Some(DamageCause::Void));
+}
+
+#[test]
+fn test_only_environmental_deaths_are_credited() {
+    let mut raw_report = read_raw("indirect_kills");
+    raw_report.damages[1].cause = raw::DamageCause::Command;
+
+    let report = Report::from_raw(raw_report).unwrap();
+    let doomed = &report.aggregates.players_damages[&"55b89885-673c-4458-a8bd-33ad9c6190b4".parse().unwrap()];
+
+    assert!(report.damages.iter().all(|damage| damage.attributed_to.is_none()));
+    assert_eq!(doomed.killed_by, Some(DamageCause::Command));
+}
+
+#[test]
+fn test_indirect_kills_attribution_can_be_disabled() {
+    let mut raw_report = read_raw("indirect_kills");
+    raw_report.settings.damages.kill_attribution_window = 0;
+
+    let report = Report::from_raw(raw_report).unwrap();
+
+    assert!(report.damages.iter().all(|damage| damage.attributed_to.is_none()));
+    assert!(report.aggregates.players_damages.values().all(|player| player.kills.is_empty()));
+}
+
+#[test]
+fn test_assists() {
+    let report = process("assists").unwrap();
+    let player = |uuid: &str| &report.aggregates.players_damages[&uuid.parse().unwrap()];
+
+    let victim = player("6a5f7a8c-b0e2-4a1b-9e4a-3e5b6f0b1a2c");
+    assert_eq!(victim.assisted_by.len(), 1);
+    assert_eq!(victim.assisted_by[0].player.name, "Jenjeur");
+    assert_eq!(victim.assisted_by[0].damage, 4);
+    assert!((victim.assisted_by[0].share - 20.0).abs() < f32::EPSILON);
+
+    assert_eq!(player("0dd34bda-c13b-473b-a887-368027ca05ca").assists.len(), 1);
+    assert_eq!(player("55b89885-673c-4458-a8bd-33ad9c6190b4").assists.len(), 0);
+
+    // Hit thirty seconds before the death: outside of the assist window.
+    assert_eq!(player("da04cd54-c6c7-4672-97c5-85663f5bccf6").assists.len(), 0);
+
+    // Hits without damage: no share can be computed.
+    let mut raw_report = read_raw("assists");
+    raw_report.damages.iter_mut().for_each(|damage| damage.damage = 0);
+
+    let report = Report::from_raw(raw_report).unwrap();
+    let assist = &report.aggregates.players_damages[&"6a5f7a8c-b0e2-4a1b-9e4a-3e5b6f0b1a2c".parse().unwrap()].assisted_by[0];
+    assert_eq!(assist.share, 0.0);
+}
+
+#[test]
+fn test_health_timeline() {
+    let point = |since_beginning: u64, health: u16, absorption: u16| HealthPoint {
+        since_beginning: Duration::from_secs(since_beginning),
+        health,
+        absorption,
+    };
+
+    let dada = "55b89885-673c-4458-a8bd-33ad9c6190b4".parse().unwrap();
+    let azenet = "6a5f7a8c-b0e2-4a1b-9e4a-3e5b6f0b1a2c".parse().unwrap();
+
+    let report = process("team_damages").unwrap();
+    assert_eq!(
+        report.aggregates.players_damages[&dada].health_timeline,
+        vec![point(0, 20, 0), point(327, 18, 0), point(387, 20, 0)]
+    );
+    assert_eq!(
+        report.aggregates.players_damages[&azenet].health_timeline,
+        vec![point(0, 20, 0), point(867, 0, 0)]
+    );
+
+    // Hits from the same cause are grouped, but the heal between them is applied in between.
+    let report = process("heal_between_hits").unwrap();
+    assert_eq!(report.damages.len(), 1);
+    assert_eq!(
+        report.aggregates.players_damages[&dada].health_timeline,
+        vec![point(0, 20, 0), point(267, 18, 0), point(272, 20, 0), point(277, 18, 0)]
+    );
+
+    let mut raw_report = read_raw("team_damages");
+    raw_report.settings.damages.absorption = 4;
+
+    let report = Report::from_raw(raw_report).unwrap();
+    assert_eq!(
+        report.aggregates.players_damages[&dada].health_timeline,
+        vec![point(0, 20, 4), point(327, 20, 2)]
+    );
+}
+
+#[test]
+fn test_placements() {
+    let report = process("simultaneous_deaths").unwrap();
+    let rank = |uuid: &str| report.aggregates.players_damages[&uuid.parse().unwrap()].rank;
+
+    assert_eq!(rank("55b89885-673c-4458-a8bd-33ad9c6190b4"), 1);
+    assert_eq!(rank("0dd34bda-c13b-473b-a887-368027ca05ca"), 2);
+    assert_eq!(rank("da04cd54-c6c7-4672-97c5-85663f5bccf6"), 2);
+    assert_eq!(rank("6a5f7a8c-b0e2-4a1b-9e4a-3e5b6f0b1a2c"), 4);
+}
+
+#[test]
+fn test_placements_with_grouped_lethal_damage() {
+    // Dada_exe's lethal hit is grouped with an earlier hit, but they died last.
+    let report = process("grouped_lethal_damage").unwrap();
+    let rank = |uuid: &str| report.aggregates.players_damages[&uuid.parse().unwrap()].rank;
+
+    assert_eq!(report.damages.len(), 2);
+    assert_eq!(rank("da04cd54-c6c7-4672-97c5-85663f5bccf6"), 1);
+    assert_eq!(rank("6a5f7a8c-b0e2-4a1b-9e4a-3e5b6f0b1a2c"), 1);
+    assert_eq!(rank("55b89885-673c-4458-a8bd-33ad9c6190b4"), 3);
+    assert_eq!(rank("0dd34bda-c13b-473b-a887-368027ca05ca"), 4);
+}
+
+#[test]
+fn test_eliminated_at_lethal_hit() {
+    let report = process("grouped_lethal_damage").unwrap();
+    let dada = &report.aggregates.players_damages[&"55b89885-673c-4458-a8bd-33ad9c6190b4".parse().unwrap()];
+
+    // The date of the group, from the first hit, is one minute after the beginning.
+    assert_eq!(dada.eliminated_at, Some(Duration::from_secs(30 * 60)));
+}
+
+#[test]
+fn test_placements_of_teams() {
+    let report = process("team_damages").unwrap();
+    let rank = |uuid: &str| report.aggregates.players_damages[&uuid.parse().unwrap()].rank;
+
+    // Jenjeur died, but their teammate survived.
+    assert_eq!(rank("55b89885-673c-4458-a8bd-33ad9c6190b4"), 1);
+    assert_eq!(rank("0dd34bda-c13b-473b-a887-368027ca05ca"), 1);
+    assert_eq!(rank("da04cd54-c6c7-4672-97c5-85663f5bccf6"), 2);
+    assert_eq!(rank("6a5f7a8c-b0e2-4a1b-9e4a-3e5b6f0b1a2c"), 2);
+
+    assert_eq!(report.aggregates.teams_damages["Rouges"].rank, 1);
+    assert_eq!(report.aggregates.teams_damages["Bleus"].rank, 2);
+}
+
+#[test]
+fn test_respawns() {
+    let report = process("respawns").unwrap();
+    let player = |uuid: &str| &report.aggregates.players_damages[&uuid.parse().unwrap()];
+
+    let winners: Vec<&String> = report.winners.iter().map(|winner| &winner.name).collect();
+    assert_eq!(winners, vec!["AmauryPi", "Dada_exe"]);
+
+    let dada = player("55b89885-673c-4458-a8bd-33ad9c6190b4");
+    assert_eq!((dada.deaths(), dada.rank), (1, 1));
+    assert!(dada.eliminated_at.is_none());
+    assert!((dada.kills_deaths_ratio - 2.0).abs() < f32::EPSILON);
+
+    let jenjeur = player("0dd34bda-c13b-473b-a887-368027ca05ca");
+    assert_eq!((jenjeur.deaths(), jenjeur.rank), (2, 3));
+    assert_eq!(jenjeur.eliminated_at, Some(Duration::from_secs(14 * 60 + 29)));
+    assert_eq!(
+        jenjeur.health_timeline,
+        vec![
+            HealthPoint { since_beginning: Duration::from_secs(0), health: 20, absorption: 0 },
+            HealthPoint { since_beginning: Duration::from_secs(267), health: 0, absorption: 0 },
+            HealthPoint { since_beginning: Duration::from_secs(867), health: 14, absorption: 0 },
+            HealthPoint { since_beginning: Duration::from_secs(869), health: 0, absorption: 0 },
+        ]
+    );
+}
+
+#[test]
+fn test_zero_lives_are_one() {
+    let mut raw_report = read_raw("respawns");
+    raw_report.settings.game_mode = GameMode::Lives { lives: 0 };
+
+    let report = Report::from_raw(raw_report).unwrap();
+    let jenjeur = &report.aggregates.players_damages[&"0dd34bda-c13b-473b-a887-368027ca05ca".parse().unwrap()];
+
+    assert_eq!(jenjeur.eliminated_at, Some(Duration::from_secs(4 * 60 + 27)));
+}
+
+#[test]
+fn test_respawns_without_elimination() {
+    let mut raw_report = read_raw("respawns");
+    raw_report.settings.game_mode = GameMode::Respawn;
+
+    let report = Report::from_raw(raw_report).unwrap();
+
+    assert_eq!(report.winners.len(), 3);
+    assert!(report.aggregates.players_damages.values().all(|player| player.rank == 1));
+}
+
+#[test]
+fn test_highlights() {
+    assert_input_with_snapshot("highlights");
+
+    let report = process("highlights").unwrap();
+    let dada = &report.aggregates.players_damages[&"55b89885-673c-4458-a8bd-33ad9c6190b4".parse().unwrap()];
+
+    assert!(dada.first_blood);
+    assert_eq!(dada.longest_kill_streak, 3);
+    assert_eq!(dada.multi_kills.len(), 1);
+    assert_eq!(dada.multi_kills[0].victims.len(), 2);
+}
+
+#[test]
+fn test_first_blood_with_grouped_lethal_damage() {
+    let report = process("grouped_lethal_damage").unwrap();
+    let player = |uuid: &str| &report.aggregates.players_damages[&uuid.parse().unwrap()];
+
+    // AmauryPi's kill is grouped with their first hit, but Azenet killed first.
+    assert!(player("6a5f7a8c-b0e2-4a1b-9e4a-3e5b6f0b1a2c").first_blood);
+    assert!(!player("da04cd54-c6c7-4672-97c5-85663f5bccf6").first_blood);
+}
+
+#[test]
+fn test_localized_highlights() {
+    let mut raw_report = read_raw("highlights");
+    raw_report.settings.summary.locale = Locale::Fr;
+
+    let report = Report::from_raw(raw_report).unwrap();
+    let titles: Vec<&str> = report.events.iter().map(|event| event.title.as_str()).collect();
+
+    assert!(titles.contains(&"Premier sang pour Dada_exe"));
+    assert!(titles.contains(&"Double meurtre pour Dada_exe"));
+    assert!(titles.contains(&"3 meurtres d’affilée pour Dada_exe"));
+}
+
+#[test]
+fn test_generated_events() {
+    let mut raw_report = read_raw("team_damages");
+    raw_report.settings.summary.generated_events = true;
+
+    // Already emitted by the plugin: must not be duplicated.
+    raw_report.events.push(serde_json::from_value(serde_json::json!({
+        "date": "2018-12-15T22:30:00+02:00",
+        "type": "RED",
+        "title": "Azenet est tombé",
+        "icon": { "type": "player", "uuid": "6a5f7a8c-b0e2-4a1b-9e4a-3e5b6f0b1a2c" }
+    })).unwrap());
+
+    // Stands for the death of the last member of the team, not for its elimination.
+    raw_report.events.push(serde_json::from_value(serde_json::json!({
+        "date": "2018-12-15T22:35:00+02:00",
+        "type": "RED",
+        "title": "AmauryPi est tombé",
+        "icon": { "type": "player", "uuid": "da04cd54-c6c7-4672-97c5-85663f5bccf6" }
+    })).unwrap());
+
+    let report = Report::from_raw(raw_report).unwrap();
+    let titles: Vec<&str> = report.events.iter().map(|event| event.title.as_str()).collect();
+
+    assert!(!titles.contains(&"AmauryPi was killed by Dada_exe"));
+    assert!(titles.contains(&"Team Bleus was eliminated"));
+
+    // Jenjeur was eliminated, but Dada_exe won for the whole team.
+    assert!(titles.contains(&"Team Rouges won the game"));
+
+    assert_snapshot!(
+        "generated_events",
+        serde_json::to_string_pretty(&report.events).unwrap()
+    );
+}
+
+#[test]
+fn test_generated_events_with_grouped_lethal_damage() {
+    let mut raw_report = read_raw("grouped_lethal_damage");
+    raw_report.settings.summary.generated_events = true;
+
+    // Emitted by the plugin at the date of the lethal hit, not at the date of the group.
+    raw_report.events.push(serde_json::from_value(serde_json::json!({
+        "date": "2018-12-15T22:30:00+02:00",
+        "type": "RED",
+        "title": "Dada_exe est mort",
+        "icon": { "type": "player", "uuid": "55b89885-673c-4458-a8bd-33ad9c6190b4" }
+    })).unwrap());
+
+    let report = Report::from_raw(raw_report).unwrap();
+    let events: Vec<(&str, Duration)> = report.events
+        .iter()
+        .map(|event| (event.title.as_str(), event.since_beginning))
+        .collect();
+
+    assert_eq!(
+        events,
+        vec![
+            ("Jenjeur was killed by Azenet", Duration::from_secs(10 * 60)),
+            ("Dada_exe est mort", Duration::from_secs(30 * 60)),
+            ("AmauryPi and Azenet won the game", Duration::from_secs(30 * 60)),
+        ]
+    );
+}
+
+#[test]
+fn test_localized_generated_events() {
+    let mut raw_report = read_raw("team_damages");
+    raw_report.settings.summary.generated_events = true;
+    raw_report.settings.summary.locale = Locale::It;
+
+    let report = Report::from_raw(raw_report).unwrap();
+    let titles: Vec<&str> = report.events.iter().map(|event| event.title.as_str()).collect();
+
+    assert!(titles.contains(&"Jenjeur è stato ucciso da AmauryPi"));
+    assert!(titles.contains(&"La squadra Bleus è stata eliminata"));
+    assert!(titles.contains(&"La squadra Rouges ha vinto la partita"));
+}
+
+#[test]
+fn test_merge_partial_reports() {
+    // Given in the wrong order on purpose: reports are sorted by date.
+    let merged = raw::merge::merge_reports(vec![read_raw("merge_part_2"), read_raw("merge_part_1")]).unwrap();
+
+    assert_eq!(merged.title, "§5KTZ §dVII");
+    assert_eq!(merged.minecraft, Some("1.16.5".to_string()));
+    assert_eq!(merged.players.len(), 3);
+    assert_eq!(merged.teams.len(), 1);
+    assert_eq!(merged.teams[0].players.len(), 2);
+    assert_eq!(merged.winners.as_ref().map(Vec::len), Some(2));
+    assert_eq!(merged.damages.len(), 2);
+    assert!(merged.damages[0].date < merged.damages[1].date);
+
+    let statistics = merged.players[0].statistics.as_ref().unwrap().generic.as_ref().unwrap();
+    assert_eq!(statistics["minecraft:jump"], 15);
+    assert_eq!(statistics["minecraft:sneak_time"], 12);
+
+    assert!(Report::from_raw(merged).is_ok());
+}
+
+#[test]
+fn test_merge_saturates_statistics() {
+    let mut second = read_raw("merge_part_2");
+    second.players[0].statistics.as_mut().unwrap().generic.as_mut().unwrap()
+        .insert("minecraft:jump".to_string(), u32::MAX);
+
+    let merged = raw::merge::merge_reports(vec![read_raw("merge_part_1"), second]).unwrap();
+
+    let statistics = merged.players[0].statistics.as_ref().unwrap().generic.as_ref().unwrap();
+    assert_eq!(statistics["minecraft:jump"], u32::MAX);
+}
+
+#[test]
+fn test_merge_reports_of_different_matches_should_fail() {
+    let mut other = read_raw("merge_part_2");
+    other.match_uuid = uuid::Uuid::nil();
+
+    assert!(raw::merge::merge_reports(vec![read_raw("merge_part_1"), other]).is_err());
+    assert!(raw::merge::merge_reports(vec![]).is_err());
+}
+
+#[test]
+fn test_season_leaderboard() {
+    let first = Report::from_raw(read_raw("merge_part_1")).unwrap();
+
+    let mut renamed = read_raw("merge_part_2");
+    renamed.players[0].name = "Dada".to_string();
+    renamed.players.push(read_raw("merge_part_1").players[1].clone());
+    let second = Report::from_raw(renamed).unwrap();
+
+    let season = report::season::Season::from_reports(&[second, first]);
+
+    assert_eq!(season.games, 2);
+    assert_eq!(season.players.len(), 3);
+
+    let dada = &season.players[0];
+    assert_eq!(dada.name, "Dada");
+    assert_eq!(dada.former_names, vec!["Dada_exe".to_string()]);
+    assert_eq!(dada.games_played, 2);
+    assert_eq!(dada.wins, 2);
+    assert_eq!(dada.average_rank, 1.0);
+    assert_eq!(dada.damages_caused_total, 4);
+
+    let statistics = dada.statistics.as_ref().unwrap().generic.as_ref().unwrap();
+    assert_eq!(statistics["minecraft:jump"], 15);
+
+    let player = |name: &str| season.players.iter().find(|p| p.name == name).unwrap();
+    assert_eq!(player("AmauryPi").kills, 1);
+    assert_eq!(player("AmauryPi").wins, 1);
+    assert_eq!(player("Jenjeur").games_played, 2);
+    assert_eq!(player("Jenjeur").wins, 1);
+    assert_eq!(player("Jenjeur").deaths, 1);
+    assert_eq!(player("Jenjeur").damages_taken_total, 20);
+}
+
+/// Processed reports stored before highlights, ratings and the other aggregates were added.
+fn read_pre_series_processed(input: &'static str) -> Report {
+    serde_json::from_str(read_input(Path::new(input_path(input).as_str())).as_str()).unwrap()
+}
+
+#[test]
+fn test_season_of_pre_series_processed_reports() {
+    let reports = [
+        read_pre_series_processed("processed_pre_series"),
+        read_pre_series_processed("processed_pre_series_weapon"),
+    ];
+
+    assert!(reports[0].damages.iter().all(|damage| damage.hits == 1));
+    assert!(reports[0].aggregates.weapons.is_empty());
+
+    let season = report::season::Season::from_reports(&reports);
+
+    assert_eq!(season.games, 2);
+    assert_eq!(season.players.len(), 2);
+
+    let dada = season.players.iter().find(|p| p.name == "Dada_exe").unwrap();
+    assert_eq!(dada.games_played, 2);
+    assert_eq!(dada.deaths, 1);
+}
+
+#[test]
+fn test_ratings() {
+    let teams = process("team_damages").unwrap();
+    let mut free_for_all = process("simultaneous_deaths").unwrap();
+    free_for_all.date = free_for_all.date + chrono::Duration::days(7);
+
+    let ratings = report::rating::Ratings::from_reports(&[teams.clone(), free_for_all.clone()]);
+    let rating = |uuid: &str| &ratings.players[&uuid.parse().unwrap()];
+
+    // Dada_exe won both games, Azenet lost both.
+    let dada = rating("55b89885-673c-4458-a8bd-33ad9c6190b4");
+    let azenet = rating("6a5f7a8c-b0e2-4a1b-9e4a-3e5b6f0b1a2c");
+
+    assert_eq!(dada.games, 2);
+    assert!(dada.mu > report::rating::INITIAL_MU);
+    assert!(azenet.mu < report::rating::INITIAL_MU);
+    assert!(dada.sigma < report::rating::INITIAL_SIGMA);
+    assert!(dada.rating > rating("0dd34bda-c13b-473b-a887-368027ca05ca").rating);
+    assert!(azenet.rating < rating("da04cd54-c6c7-4672-97c5-85663f5bccf6").rating);
+
+    // Same games, same ratings, whatever the order they are given in.
+    assert_eq!(ratings, report::rating::Ratings::from_reports(&[free_for_all, teams]));
+}
+
+#[test]
+fn test_ratings_of_incomplete_reports() {
+    // A team member missing from the players of the report.
+    let mut teams = process("team_damages").unwrap();
+    teams.players.retain(|player| player.name != "Azenet");
+
+    let ratings = report::rating::Ratings::from_reports(&[
+        teams,
+        read_pre_series_processed("processed_pre_series"),
+    ]);
+
+    let azenet = &ratings.players[&"6a5f7a8c-b0e2-4a1b-9e4a-3e5b6f0b1a2c".parse().unwrap()];
+    assert_eq!(azenet.name, "Azenet");
+    assert_eq!(azenet.games, 1);
+
+    let moribus = &ratings.players[&"e4953e0c-eaff-4aaf-a597-d2a7794b1684".parse().unwrap()];
+    assert_eq!(moribus.games, 1);
+}
+
+#[test]
+fn test_diff() {
+    let before = process("merge_part_1").unwrap();
+    let after = Report::from_raw(
+        raw::merge::merge_reports(vec![read_raw("merge_part_1"), read_raw("merge_part_2")]).unwrap(),
+    )
+    .unwrap();
+
+    let diff = report::diff::ReportDiff::between(&before, &after).unwrap();
+
+    assert_snapshot!("diff_text", diff.to_string());
+    assert_snapshot!("diff_json", serde_json::to_string_pretty(&diff).unwrap());
+
+    assert!(report::diff::ReportDiff::between(&after, &after).unwrap().is_empty());
+}
+
+#[test]
+fn test_diff_reports_of_different_matches_should_fail() {
+    let before = process("merge_part_1").unwrap();
+    let mut after = before.clone();
+    after.match_uuid = uuid::Uuid::nil();
+
+    assert!(report::diff::ReportDiff::between(&before, &after).is_err());
+}
+
+#[test]
+fn test_diff_with_pre_series_processed_report() {
+    let before = read_pre_series_processed("processed_pre_series");
+    let after = process("grouped_damages_multiple_actors_environment").unwrap();
+
+    let diff = report::diff::ReportDiff::between(&before, &after).unwrap();
+
+    assert!(diff.players_added.is_empty());
+    assert!(diff.players_removed.is_empty());
+
+    // The same game: deaths are not counted in pre-series reports, but the killed player died once.
+    assert!(diff.players_changed.iter().all(|player| player.deaths.is_none()));
+    assert!(diff.players_changed.is_empty());
+}
+
+#[test]
+fn test_head_to_head() {
+    let report = process("team_damages").unwrap();
+    let head_to_head = |attacker: &str, victim: &str| {
+        report.aggregates.head_to_head[&attacker.parse().unwrap()][&victim.parse().unwrap()].clone()
+    };
+
+    let jenjeur = "0dd34bda-c13b-473b-a887-368027ca05ca";
+    let amaury = "da04cd54-c6c7-4672-97c5-85663f5bccf6";
+
+    let jenjeur_to_amaury = head_to_head(jenjeur, amaury);
+    assert_eq!((jenjeur_to_amaury.damages, jenjeur_to_amaury.hits, jenjeur_to_amaury.kills), (4, 1, 0));
+
+    let amaury_to_jenjeur = head_to_head(amaury, jenjeur);
+    assert_eq!((amaury_to_jenjeur.damages, amaury_to_jenjeur.hits, amaury_to_jenjeur.kills), (20, 1, 1));
+
+    let teams = &report.aggregates.teams_head_to_head;
+    assert_eq!(teams["Rouges"]["Rouges"].damages, 2);
+    assert_eq!(teams["Bleus"]["Rouges"].kills, 1);
+}
+
+#[test]
+fn test_weapons() {
+    let report = process("weapons").unwrap();
+    let weapons = |uuid: &str| &report.aggregates.players_damages[&uuid.parse().unwrap()].weapons;
+    fn summary(weapon: &WeaponAggregate) -> (&str, usize, u32, u32, u32) {
+        (weapon.id.as_str(), weapon.enchantments.len(), weapon.damages, weapon.hits, weapon.kills)
+    }
+
+    // Enchantments in a different order are the same weapon.
+    let dada: Vec<_> = weapons("55b89885-673c-4458-a8bd-33ad9c6190b4").iter().map(summary).collect();
+    assert_eq!(dada, vec![("minecraft:diamond_sword", 2, 20, 3, 1), ("minecraft:diamond_sword", 0, 5, 1, 0)]);
+
+    // Bare-handed damages are ignored.
+    let amaury: Vec<_> = weapons("da04cd54-c6c7-4672-97c5-85663f5bccf6").iter().map(summary).collect();
+    assert_eq!(amaury, vec![("minecraft:bow", 0, 6, 2, 0)]);
+
+    // Entities weapons are included in the game weapons.
+    let global: Vec<_> = report.aggregates.weapons.iter().map(summary).collect();
+    assert_eq!(global, vec![
+        ("minecraft:bow", 0, 21, 3, 1),
+        ("minecraft:diamond_sword", 2, 20, 3, 1),
+        ("minecraft:diamond_sword", 0, 5, 1, 0),
+    ]);
+}
+
+#[test]
+fn test_item_names() {
+    let item = |json: &str| serde_json::from_str::<Item>(json).unwrap();
+
+    let sword = item(r#"{
+        "id": "minecraft:diamond_sword",
+        "tag": {"Enchantments": [{"id": "minecraft:sharpness", "lvl": 3}, {"id": "minecraft:unbreaking", "lvl": 2}]}
+    }"#);
+
+    assert_eq!(sword.display_name(Locale::En), "Diamond Sword (Sharpness III, Unbreaking II)");
+    assert_eq!(sword.display_name(Locale::Fr), "Épée en diamant (Tranchant III, Solidité II)");
+    assert_eq!(sword.display_name(Locale::It), "Spada di diamante (Affilatura III, Indistruttibilità II)");
+
+    let bow = item(r#"{
+        "id": "minecraft:bow",
+        "tag": {"Enchantments": [{"id": "minecraft:infinity", "lvl": 1}, {"id": "minecraft:vanishing_curse"}]}
+    }"#);
+
+    assert_eq!(bow.display_name(Locale::En), "Bow (Infinity, Curse of Vanishing)");
+    assert_eq!(bow.display_name(Locale::Fr), "Arc (Infinité, Malédiction de disparition)");
+
+    // Items without translation fall back to English.
+    assert_eq!(item(r#"{"id": "minecraft:golden_apple"}"#).display_name(Locale::It), "Golden Apple");
+
+    // Custom names are kept, with their color codes, either raw or as JSON text components.
+    let renamed = item(r#"{"id": "minecraft:iron_axe", "tag": {"display": {"Name": "§6Excalibur"}}}"#);
+    assert_eq!(renamed.display_name(Locale::Fr), "§6Excalibur");
+
+    let renamed = item(r#"{"id": "minecraft:iron_axe", "tag": {"display": {"Name": "{\"text\":\"Excal\",\"extra\":[{\"text\":\"ibur\"}]}"}}}"#);
+    assert_eq!(renamed.display_name(Locale::En), "Excalibur");
+
+    let renamed = item(r#"{"id": "minecraft:iron_axe", "tag": {"display": {"Name": "{\"text\":\"\",\"extra\":[\"Excalibur\"]}"}}}"#);
+    assert_eq!(renamed.display_name(Locale::En), "Excalibur");
+
+    assert_eq!("it".parse::<Locale>().unwrap(), Locale::It);
+    assert!("de".parse::<Locale>().is_err());
+}
+
+#[test]
+fn test_potion_effects() {
+    let item = |json: &str| serde_json::from_str::<Item>(json).unwrap();
+    let names = |item: &Item| -> Vec<String> {
+        item.potion_effects().iter().map(|effect| effect.display_name(Locale::En)).collect()
+    };
+
+    let healing = item(r#"{"id": "minecraft:splash_potion", "tag": {"Potion": "minecraft:strong_healing"}}"#);
+    assert_eq!(names(&healing), vec!["Instant Health II"]);
+    assert_eq!(healing.display_name(Locale::En), "Splash Potion (Instant Health II)");
+    assert_eq!(healing.display_name(Locale::Fr), "Potion jetable (Soin instantané II)");
+
+    let poison = item(r#"{"id": "minecraft:potion", "tag": {"Potion": "minecraft:long_poison"}}"#);
+    assert_eq!(names(&poison), vec!["Poison (1:30)"]);
+
+    // Lingering potions last four times less.
+    let poison = item(r#"{"id": "minecraft:lingering_potion", "tag": {"Potion": "minecraft:poison"}}"#);
+    assert_eq!(poison.potion_effects()[0].duration, Some(Duration::from_millis(11250)));
+
+    let turtle = item(r#"{"id": "minecraft:potion", "tag": {"Potion": "turtle_master"}}"#);
+    assert_eq!(names(&turtle), vec!["Slowness IV (0:20)", "Resistance III (0:20)"]);
+
+    // Custom effects use numeric identifiers, amplifiers starting at 0, and durations in ticks.
+    let custom = item(r#"{
+        "id": "minecraft:splash_potion",
+        "tag": {"CustomPotionEffects": [{"Id": 20, "Amplifier": 1, "Duration": 200}, {"Id": 7, "Amplifier": 0}, {"Id": 200, "Amplifier": 0}]}
+    }"#);
+    assert_eq!(names(&custom), vec!["Wither II (0:10)", "Instant Damage"]);
+    assert_eq!(custom.potion_effects()[1].effect, Effect::InstantDamage);
+    assert_eq!(Effect::InstantDamage.id(), 7);
+    assert_eq!(Effect::from_id(Effect::Darkness.id()), Some(Effect::Darkness));
+
+    assert!(item(r#"{"id": "minecraft:potion", "tag": {"Potion": "minecraft:awkward"}}"#).potion_effects().is_empty());
+    assert!(item(r#"{"id": "minecraft:diamond_sword"}"#).potion_effects().is_empty());
+}
+
+#[test]
+fn test_snbt_items() {
+    let item = snbt::parse_item(
+        r#"{id:"minecraft:splash_potion", Count:2b, tag:{Unbreakable:true, display:{Name:'{"text":"Boom"}', Lore:["a", "b"]}, CustomPotionEffects:[{Id:7b,Amplifier:1b,Duration:1}], Potion:minecraft:harming, Arr:[I; 1, 2], F:1.5f}}"#,
+    ).unwrap();
+
+    assert_eq!(item.id, "minecraft:splash_potion");
+    assert_eq!(item.count, 2);
+
+    let tag = item.tag.as_ref().unwrap();
+    assert_eq!(tag.unbreakable, Some(1));
+    assert_eq!(tag.potion, Some("minecraft:harming".to_string()));
+    assert_eq!(tag.display.as_ref().unwrap().lore.as_ref().unwrap().len(), 2);
+    assert_eq!(item.display_name(Locale::En), "Boom (Instant Damage, Instant Damage II)");
+
+    assert!(snbt::parse_item(r#"{id:"minecraft:bow""#).is_err());
+    assert!(snbt::parse_item(r#"{id:"minecraft:bow"} trailing"#).is_err());
+    assert!(snbt::parse_item(r#"{Count:1b}"#).is_err());
+
+    // Mapping errors tell where the item is invalid.
+    let error = snbt::parse_item(r#"{id:"minecraft:bow", tag:{Enchantments:[{id:"power", lvl:"five"}]}}"#)
+        .unwrap_err()
+        .to_string();
+    assert!(error.starts_with("Invalid SNBT item (at tag.Enchantments[0].lvl): "), "{}", error);
+
+    // Nesting is limited, rather than overflowing the stack.
+    let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+    assert!(snbt::parse(&nested(128)).is_ok());
+    assert!(matches!(
+        snbt::parse(&nested(100_000)),
+        Err(report::errors::InvalidReportError::InvalidSnbt { position: 128, .. })
+    ));
+
+    // Weapons given as SNBT strings are processed like the JSON ones.
+    let json = process("weapons").unwrap();
+    let snbt = process("snbt_weapons").unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&json.aggregates.weapons).unwrap(),
+        serde_json::to_value(&snbt.aggregates.weapons).unwrap()
+    );
+}
+
+#[test]
+fn test_item_components() {
+    let item = |json: &str| serde_json::from_str::<Item>(json).unwrap();
+
+    // Minecraft 1.20.5 to 1.21.4.
+    let sword = item(r#"{
+        "id": "minecraft:diamond_sword",
+        "count": 1,
+        "components": {
+            "minecraft:enchantments": {"levels": {"minecraft:sharpness": 3}, "show_in_tooltip": false},
+            "minecraft:custom_name": "{\"text\":\"Excalibur\",\"color\":\"gold\"}",
+            "minecraft:unbreakable": {}
+        }
+    }"#);
+
+    // Minecraft 1.21.5+.
+    let modern_sword = item(r#"{
+        "id": "minecraft:diamond_sword",
+        "components": {
+            "minecraft:enchantments": {"minecraft:sharpness": 3},
+            "minecraft:custom_name": {"text": "Excalibur", "color": "gold"},
+            "minecraft:unbreakable": {}
+        }
+    }"#);
+
+    let legacy_sword = item(r#"{
+        "id": "minecraft:diamond_sword",
+        "Count": 1,
+        "tag": {
+            "Enchantments": [{"id": "minecraft:sharpness", "lvl": 3}],
+            "display": {"Name": "{\"text\":\"Excalibur\",\"color\":\"gold\"}"},
+            "Unbreakable": 1
+        }
+    }"#);
+
+    assert_eq!(sword, legacy_sword);
+    assert_eq!(modern_sword.display_name(Locale::En), "Excalibur (Sharpness III)");
+    assert_eq!(modern_sword.tag.as_ref().unwrap().unbreakable, Some(1));
+
+    let plain_sword = item(r#"{
+        "id": "minecraft:diamond_sword",
+        "components": {"minecraft:custom_name": "\"Excalibur\""}
+    }"#);
+    assert_eq!(plain_sword.display_name(Locale::En), "Excalibur");
+
+    let potion = item(r#"{
+        "id": "minecraft:splash_potion",
+        "components": {
+            "minecraft:potion_contents": {
+                "potion": "minecraft:strong_poison",
+                "custom_effects": [{"id": "minecraft:wither", "amplifier": 1, "duration": 200}, {"id": "minecraft:unknown"}]
+            }
+        }
+    }"#);
+
+    assert_eq!(potion.display_name(Locale::En), "Splash Potion (Poison II (0:21), Wither II (0:10))");
+
+    let potion = item(r#"{"id": "minecraft:potion", "components": {"minecraft:potion_contents": "minecraft:healing"}}"#);
+    assert_eq!(potion.display_name(Locale::En), "Potion (Instant Health)");
+
+    // Infinite effects (`-1`), and effects added in Minecraft 1.20.5 and 1.21.
+    let potion = item(r#"{
+        "id": "minecraft:potion",
+        "components": {
+            "minecraft:potion_contents": {
+                "custom_effects": [{"id": "minecraft:speed", "duration": -1}, {"id": "minecraft:oozing", "duration": 200}, {"id": "minecraft:trial_omen", "amplifier": 2, "duration": -1}]
+            }
+        }
+    }"#);
+
+    let effects = potion.potion_effects();
+    assert_eq!(effects[0].duration, None);
+    assert!(effects[0].infinite);
+    assert_eq!(effects[1].effect, Effect::Oozing);
+    assert_eq!(Effect::Oozing.id(), 38);
+    assert!(!effects[1].infinite);
+    assert_eq!(potion.display_name(Locale::En), "Potion (Speed (∞), Oozing (0:10), Trial Omen III (∞))");
+
+    // Processed items only keep the normalized tag.
+    let processed = serde_json::to_value(&modern_sword).unwrap();
+    assert!(processed.get("components").is_none());
+    assert_eq!(serde_json::from_value::<Item>(processed).unwrap(), modern_sword);
+}
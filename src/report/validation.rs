@@ -0,0 +1,211 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, FixedOffset};
+use uuid::Uuid;
+
+use crate::report::errors::InvalidReportError;
+use crate::report::event::EventIcon;
+use crate::report::raw::{DamageCause, Player, Report};
+
+/// A single problem found in a raw report, located using a JSON-pointer-like path
+/// (e.g. `/damages/3/damagee`) relative to the report root.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct ValidationProblem {
+    pub path: String,
+    pub code: String,
+    pub message: String,
+}
+
+impl ValidationProblem {
+    pub fn new(path: String, error: InvalidReportError) -> Self {
+        Self {
+            path,
+            code: error.as_ref().to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Walks the whole raw report and returns every problem found, in the order they appear in
+/// the report, instead of stopping at the first one like `Report::from_raw` does.
+///
+/// An empty vec means the report is valid.
+pub fn validate_report(report: &Report) -> Vec<ValidationProblem> {
+    let mut validator = Validator {
+        players: HashSet::new(),
+        begin: report.date,
+        problems: Vec::new(),
+    };
+
+    validator.validate_players(report);
+    validator.validate_teams(report);
+    validator.validate_winners(report);
+    validator.validate_damages(report);
+    validator.validate_heals(report);
+    validator.validate_events(report);
+
+    validator.problems
+}
+
+/// Repairs a raw report so it can be processed even if it is broken:
+///
+/// - players declared several times are only kept once;
+/// - players in several teams are only kept in the first one;
+/// - players referenced but never declared are replaced by placeholder players, named after
+///   their UUID.
+///
+/// Problems that cannot break the processing (like dates before the beginning of the game) are
+/// left untouched.
+pub fn repair_report(mut report: Report) -> Report {
+    let mut players: HashSet<Uuid> = HashSet::new();
+    report.players.retain(|player| players.insert(player.uuid));
+
+    let mut players_in_teams: HashSet<Uuid> = HashSet::new();
+    for team in report.teams.iter_mut() {
+        team.players.retain(|uuid| players_in_teams.insert(*uuid));
+    }
+
+    let referenced = report
+        .teams
+        .iter()
+        .flat_map(|team| team.players.iter())
+        .chain(report.winners.iter().flatten())
+        .chain(report.damages.iter().flat_map(|damage| {
+            match &damage.cause {
+                DamageCause::Player(cause) => Some(&cause.player),
+                _ => None,
+            }
+            .into_iter()
+            .chain(std::iter::once(&damage.damagee))
+        }))
+        .chain(report.heals.iter().map(|heal| &heal.healed));
+
+    let mut placeholders: Vec<Player> = Vec::new();
+    for uuid in referenced {
+        if players.insert(*uuid) {
+            placeholders.push(Player {
+                uuid: *uuid,
+                name: uuid.to_hyphenated().to_string(),
+                tag_line: None,
+                tag_line_secondary: None,
+                tag_line_details: None,
+                statistics: None,
+            });
+        }
+    }
+
+    report.players.extend(placeholders);
+
+    report
+}
+
+struct Validator {
+    players: HashSet<Uuid>,
+    begin: DateTime<FixedOffset>,
+    problems: Vec<ValidationProblem>,
+}
+
+impl Validator {
+    fn problem(&mut self, path: String, error: InvalidReportError) {
+        self.problems.push(ValidationProblem::new(path, error));
+    }
+
+    fn check_reference(&mut self, path: String, uuid: &Uuid) {
+        if !self.players.contains(uuid) {
+            self.problem(
+                path,
+                InvalidReportError::MissingPlayerReference { uuid: *uuid },
+            );
+        }
+    }
+
+    fn check_date(&mut self, path: String, date: &DateTime<FixedOffset>) {
+        if *date < self.begin {
+            self.problem(
+                path,
+                InvalidReportError::DateBeforeBeginning {
+                    date: *date,
+                    begin: self.begin,
+                },
+            );
+        }
+    }
+
+    fn validate_players(&mut self, report: &Report) {
+        for (index, player) in report.players.iter().enumerate() {
+            if !self.players.insert(player.uuid) {
+                self.problem(
+                    format!("/players/{}/uuid", index),
+                    InvalidReportError::DuplicatePlayer { uuid: player.uuid },
+                );
+            }
+        }
+    }
+
+    fn validate_teams(&mut self, report: &Report) {
+        let mut players_teams: HashMap<Uuid, &String> = HashMap::new();
+
+        for (team_index, team) in report.teams.iter().enumerate() {
+            for (index, uuid) in team.players.iter().enumerate() {
+                let path = format!("/teams/{}/players/{}", team_index, index);
+
+                self.check_reference(path.clone(), uuid);
+
+                match players_teams.get(uuid) {
+                    Some(first_team) => self.problem(
+                        path,
+                        InvalidReportError::PlayerInSeveralTeams {
+                            uuid: *uuid,
+                            first_team: (*first_team).clone(),
+                            second_team: team.name.clone(),
+                        },
+                    ),
+                    None => {
+                        players_teams.insert(*uuid, &team.name);
+                    }
+                }
+            }
+        }
+    }
+
+    fn validate_winners(&mut self, report: &Report) {
+        if let Some(winners) = &report.winners {
+            for (index, uuid) in winners.iter().enumerate() {
+                self.check_reference(format!("/winners/{}", index), uuid);
+            }
+        }
+    }
+
+    fn validate_damages(&mut self, report: &Report) {
+        for (index, damage) in report.damages.iter().enumerate() {
+            self.check_date(format!("/damages/{}/date", index), &damage.date);
+
+            if let DamageCause::Player(cause) = &damage.cause {
+                self.check_reference(format!("/damages/{}/cause/player", index), &cause.player);
+            }
+
+            if let Some(damager) = &damage.damager {
+                self.check_reference(format!("/damages/{}/damager", index), damager);
+            }
+
+            self.check_reference(format!("/damages/{}/damagee", index), &damage.damagee);
+        }
+    }
+
+    fn validate_heals(&mut self, report: &Report) {
+        for (index, heal) in report.heals.iter().enumerate() {
+            self.check_date(format!("/heals/{}/date", index), &heal.date);
+            self.check_reference(format!("/heals/{}/healed", index), &heal.healed);
+        }
+    }
+
+    fn validate_events(&mut self, report: &Report) {
+        for (index, event) in report.events.iter().enumerate() {
+            self.check_date(format!("/events/{}/date", index), &event.date);
+
+            if let EventIcon::Player { uuid } = &event.icon {
+                self.check_reference(format!("/events/{}/icon/uuid", index), uuid);
+            }
+        }
+    }
+}